@@ -151,6 +151,50 @@ namespace Psyche
             return result;
         }
 
+        public NAPI.UID[] GetInputsLayout()
+        {
+            NAPI.UID[] result = null;
+            NAPI.BrainIoLayoutSensors(
+                handle,
+                (context, uids, count) =>
+                {
+                    if (uids != IntPtr.Zero && (uint)count > 0)
+                    {
+                        result = MakeUids(uids, count);
+                    }
+                },
+                IntPtr.Zero
+            );
+            return result;
+        }
+
+        public NAPI.UID[] GetOutputsLayout()
+        {
+            NAPI.UID[] result = null;
+            NAPI.BrainIoLayoutEffectors(
+                handle,
+                (context, uids, count) =>
+                {
+                    if (uids != IntPtr.Zero && (uint)count > 0)
+                    {
+                        result = MakeUids(uids, count);
+                    }
+                },
+                IntPtr.Zero
+            );
+            return result;
+        }
+
+        public bool Feed(double[] inputs)
+        {
+            return NAPI.BrainFeed(handle, inputs, (UIntPtr)inputs.Length);
+        }
+
+        public bool ReadOutputs(double[] outputs)
+        {
+            return NAPI.BrainReadOutputs(handle, outputs, (UIntPtr)outputs.Length);
+        }
+
         public bool SensorTriggerImpulse(NAPI.UID uid, double potential)
         {
             return NAPI.BrainBrainSensorTriggerImpulse(handle, uid, potential);
@@ -168,6 +212,13 @@ namespace Psyche
             return false;
         }
 
+        public bool ApplyReward(double value)
+        {
+            return handle == UIntPtr.Zero
+                ? false
+                : NAPI.BrainApplyReward(handle, value);
+        }
+
         public Brain OffspringMutated(ref NAPI.OffspringBuilderConfig config)
         {
             var handle = NAPI.BrainOffspringMutated(ref config, this.handle);
@@ -7,6 +7,7 @@ namespace Psyche
     {
         private const string LibName = "psyche_capi";
         private const CallingConvention LibCall = CallingConvention.Cdecl;
+        public const int StatsHistogramBins = 8;
 
         [UnmanagedFunctionPointer(LibCall)]
         public delegate void OnResultBytes(
@@ -143,6 +144,17 @@ namespace Psyche
             public UIntPtr OutgoingNeuronConnectionsMax;
             public double SynapsesReceptorsMin;
             public double SynapsesReceptorsMax;
+            public double MeanFiringRate;
+            public double ActiveNeurons;
+            [MarshalAs(UnmanagedType.ByValArray, SizeConst = StatsHistogramBins)]
+            public UIntPtr[] SynapsesReceptorsHistogram;
+            [MarshalAs(UnmanagedType.ByValArray, SizeConst = StatsHistogramBins)]
+            public UIntPtr[] IncomingNeuronConnectionsHistogram;
+            [MarshalAs(UnmanagedType.ByValArray, SizeConst = StatsHistogramBins)]
+            public UIntPtr[] OutgoingNeuronConnectionsHistogram;
+            public UIntPtr StronglyConnectedComponents;
+            public double SensorEffectorPathLength;
+            public UIntPtr ReachableEffectors;
         }
 
         [DllImport(LibName, CallingConvention = LibCall, EntryPoint = "psyche_brain_builder_to_string", CharSet = CharSet.Ansi)]
@@ -270,6 +282,40 @@ namespace Psyche
             IntPtr resultContext
         );
 
+        [DllImport(LibName, CallingConvention = LibCall, EntryPoint = "psyche_brain_io_layout_sensors", CharSet = CharSet.Ansi)]
+        [return: MarshalAs(UnmanagedType.I1)]
+        public extern static bool BrainIoLayoutSensors(
+            UIntPtr handle,
+            [MarshalAs(UnmanagedType.FunctionPtr)]
+            OnResultUids result,
+            IntPtr resultContext
+        );
+
+        [DllImport(LibName, CallingConvention = LibCall, EntryPoint = "psyche_brain_io_layout_effectors", CharSet = CharSet.Ansi)]
+        [return: MarshalAs(UnmanagedType.I1)]
+        public extern static bool BrainIoLayoutEffectors(
+            UIntPtr handle,
+            [MarshalAs(UnmanagedType.FunctionPtr)]
+            OnResultUids result,
+            IntPtr resultContext
+        );
+
+        [DllImport(LibName, CallingConvention = LibCall, EntryPoint = "psyche_brain_feed", CharSet = CharSet.Ansi)]
+        [return: MarshalAs(UnmanagedType.I1)]
+        public extern static bool BrainFeed(
+            UIntPtr handle,
+            [In] double[] inputs,
+            UIntPtr count
+        );
+
+        [DllImport(LibName, CallingConvention = LibCall, EntryPoint = "psyche_brain_read_outputs", CharSet = CharSet.Ansi)]
+        [return: MarshalAs(UnmanagedType.I1)]
+        public extern static bool BrainReadOutputs(
+            UIntPtr handle,
+            [Out] double[] outputs,
+            UIntPtr count
+        );
+
         [DllImport(LibName, CallingConvention = LibCall, EntryPoint = "psyche_brain_sensor_trigger_impulse", CharSet = CharSet.Ansi)]
         [return: MarshalAs(UnmanagedType.I1)]
         public extern static bool BrainBrainSensorTriggerImpulse(
@@ -286,6 +332,13 @@ namespace Psyche
             ref double outResult
         );
 
+        [DllImport(LibName, CallingConvention = LibCall, EntryPoint = "psyche_brain_apply_reward", CharSet = CharSet.Ansi)]
+        [return: MarshalAs(UnmanagedType.I1)]
+        public extern static bool BrainApplyReward(
+            UIntPtr handle,
+            double value
+        );
+
         [DllImport(LibName, CallingConvention = LibCall, EntryPoint = "psyche_offspring_mutated", CharSet = CharSet.Ansi)]
         public extern static UIntPtr BrainOffspringMutated(
             ref OffspringBuilderConfig config,
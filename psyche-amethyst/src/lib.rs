@@ -7,7 +7,7 @@ use amethyst::{
         prelude::DispatcherBuilder, Component, DenseVecStorage, Join, Read, System, WriteStorage,
     },
 };
-use psyche::core::{brain::Brain, brain_builder::BrainBuilder};
+use psyche::core::{brain::Brain, brain_builder::BrainBuilder, Scalar};
 
 #[derive(Debug, Clone, Default, PartialEq)]
 pub struct BrainComponent {
@@ -28,6 +28,12 @@ impl BrainComponent {
             brain: builder.build(),
         }
     }
+
+    /// Reinforces recent brain activity with given reward (negative value punishes).
+    #[inline]
+    pub fn apply_reward(&mut self, value: Scalar) {
+        self.brain.apply_reward(value);
+    }
 }
 
 #[derive(Default)]
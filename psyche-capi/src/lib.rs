@@ -3,7 +3,9 @@ extern crate psyche;
 #[macro_use]
 extern crate lazy_static;
 
-use psyche::core::brain::{Brain, BrainActivityStats as PsycheBrainActivityStats};
+use psyche::core::brain::{
+    Brain, BrainActivityStats as PsycheBrainActivityStats, STATS_HISTOGRAM_BINS,
+};
 use psyche::core::brain_builder::BrainBuilder;
 use psyche::core::config::Config;
 use psyche::core::id::ID;
@@ -119,6 +121,7 @@ unsafe fn brain_builder_from_config(this: *const BrainBuilderConfig) -> BrainBui
         synapse_overdose_receptors: (*this).synapse_overdose_receptors.into_option(),
         synapse_propagation_decay: (*this).synapse_propagation_decay,
         synapse_new_connection_receptors: (*this).synapse_new_connection_receptors.into_option(),
+        ..Default::default()
     };
     BrainBuilder::new()
         .config(config)
@@ -225,6 +228,14 @@ pub struct BrainActivityStats {
     pub outgoing_neuron_connections_max: usize,
     pub synapses_receptors_min: Scalar,
     pub synapses_receptors_max: Scalar,
+    pub mean_firing_rate: Scalar,
+    pub active_neurons: Scalar,
+    pub synapses_receptors_histogram: [usize; STATS_HISTOGRAM_BINS],
+    pub incoming_neuron_connections_histogram: [usize; STATS_HISTOGRAM_BINS],
+    pub outgoing_neuron_connections_histogram: [usize; STATS_HISTOGRAM_BINS],
+    pub strongly_connected_components: usize,
+    pub sensor_effector_path_length: Scalar,
+    pub reachable_effectors: usize,
 }
 
 impl Into<BrainActivityStats> for PsycheBrainActivityStats {
@@ -248,6 +259,14 @@ impl Into<BrainActivityStats> for PsycheBrainActivityStats {
             outgoing_neuron_connections_max: self.outgoing_neuron_connections.end,
             synapses_receptors_min: self.synapses_receptors.start,
             synapses_receptors_max: self.synapses_receptors.end,
+            mean_firing_rate: self.mean_firing_rate,
+            active_neurons: self.active_neurons,
+            synapses_receptors_histogram: self.synapses_receptors_histogram,
+            incoming_neuron_connections_histogram: self.incoming_neuron_connections_histogram,
+            outgoing_neuron_connections_histogram: self.outgoing_neuron_connections_histogram,
+            strongly_connected_components: self.strongly_connected_components,
+            sensor_effector_path_length: self.sensor_effector_path_length,
+            reachable_effectors: self.reachable_effectors,
         }
     }
 }
@@ -407,6 +426,9 @@ pub extern "C" fn psyche_deserialize_bytes_brain(
 ) -> Handle {
     let bytes = bytes_from_raw(bytes, size);
     if let Ok(mut brain) = brain_from_bytes(&bytes) {
+        if brain.validate().is_err() {
+            return 0;
+        }
         if kill_impulses {
             brain.kill_impulses();
         }
@@ -430,6 +452,9 @@ pub extern "C" fn psyche_deserialize_json_brain(
 ) -> Handle {
     let json = string_from_raw_unsized(json as *const libc::c_uchar);
     if let Ok(mut brain) = brain_from_json(&json) {
+        if brain.validate().is_err() {
+            return 0;
+        }
         if kill_impulses {
             brain.kill_impulses();
         }
@@ -453,6 +478,9 @@ pub extern "C" fn psyche_deserialize_yaml_brain(
 ) -> Handle {
     let yaml = string_from_raw_unsized(yaml as *const libc::c_uchar);
     if let Ok(mut brain) = brain_from_yaml(&yaml) {
+        if brain.validate().is_err() {
+            return 0;
+        }
         if kill_impulses {
             brain.kill_impulses();
         }
@@ -513,6 +541,94 @@ pub extern "C" fn psyche_brain_get_effectors(
     false
 }
 
+#[no_mangle]
+pub extern "C" fn psyche_brain_io_layout_sensors(
+    handle: Handle,
+    result: fn(*mut libc::c_void, *const UID, usize),
+    result_context: *mut libc::c_void,
+) -> bool {
+    if (result as *const libc::c_void).is_null() {
+        return false;
+    }
+    if let Some(brain) = BRAINS.lock().unwrap().get(&handle) {
+        let uids = brain
+            .io_layout()
+            .sensors
+            .iter()
+            .map(|id| UID::from_id(*id))
+            .collect::<Vec<_>>();
+        result(result_context, uids.as_ptr(), uids.len());
+        return true;
+    }
+    result(null_mut(), null(), 0);
+    false
+}
+
+#[no_mangle]
+pub extern "C" fn psyche_brain_io_layout_effectors(
+    handle: Handle,
+    result: fn(*mut libc::c_void, *const UID, usize),
+    result_context: *mut libc::c_void,
+) -> bool {
+    if (result as *const libc::c_void).is_null() {
+        return false;
+    }
+    if let Some(brain) = BRAINS.lock().unwrap().get(&handle) {
+        let uids = brain
+            .io_layout()
+            .effectors
+            .iter()
+            .map(|id| UID::from_id(*id))
+            .collect::<Vec<_>>();
+        result(result_context, uids.as_ptr(), uids.len());
+        return true;
+    }
+    result(null_mut(), null(), 0);
+    false
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn psyche_brain_feed(
+    handle: Handle,
+    inputs: *const Scalar,
+    count: usize,
+) -> bool {
+    if inputs.is_null() && count > 0 {
+        return false;
+    }
+    if let Some(brain) = BRAINS.lock().unwrap().get_mut(&handle) {
+        let inputs = if count > 0 {
+            std::slice::from_raw_parts(inputs, count)
+        } else {
+            &[]
+        };
+        brain.feed(inputs).is_ok()
+    } else {
+        false
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn psyche_brain_read_outputs(
+    handle: Handle,
+    outputs: *mut Scalar,
+    count: usize,
+) -> bool {
+    if outputs.is_null() && count > 0 {
+        return false;
+    }
+    if let Some(brain) = BRAINS.lock().unwrap().get_mut(&handle) {
+        let outputs = if count > 0 {
+            std::slice::from_raw_parts_mut(outputs, count)
+        } else {
+            &mut []
+        };
+        brain.read_outputs(outputs).is_ok()
+    } else {
+        false
+    }
+}
+
 #[no_mangle]
 pub extern "C" fn psyche_brain_sensor_trigger_impulse(
     handle: Handle,
@@ -546,6 +662,16 @@ pub unsafe extern "C" fn psyche_brain_effector_potential_release(
     false
 }
 
+#[no_mangle]
+pub extern "C" fn psyche_brain_apply_reward(handle: Handle, value: Scalar) -> bool {
+    if let Some(brain) = BRAINS.lock().unwrap().get_mut(&handle) {
+        brain.apply_reward(value);
+        true
+    } else {
+        false
+    }
+}
+
 #[no_mangle]
 pub unsafe extern "C" fn psyche_offspring_mutated(
     config: *const OffspringBuilderConfig,
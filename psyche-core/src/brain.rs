@@ -1,14 +1,27 @@
+use crate::brain_index::BrainIndex;
+use crate::compatibility::align_neurons;
 use crate::config::Config;
-use crate::effector::{Effector, EffectorID};
+use crate::effector::{Effector, EffectorID, EffectorMode};
 use crate::error::*;
+use crate::event::BrainEvent;
+use crate::graph;
 use crate::id::ID;
-use crate::neuron::{Impulse, Neuron, NeuronID, Position, Synapse};
-use crate::sensor::{Sensor, SensorID};
+use crate::label::Label;
+use crate::lineage::LineageID;
+use crate::neuron::{Impulse, Neuron, NeuronData, NeuronID, Position, Synapse, SynapseData};
+use crate::neuron_model::NeuronModelType;
+use crate::plasticity::PlasticityRule;
+use crate::rng::BrainRng;
+use crate::sensor::{Sensor, SensorID, SensorMode};
+use crate::topology::{BrainActivityDelta, BrainTopology};
 use crate::Scalar;
 use rand::{thread_rng, Rng};
 #[cfg(feature = "parallel")]
 use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
 use std::ops::Range;
 
 #[cfg(feature = "parallel")]
@@ -49,6 +62,8 @@ pub mod activity {
 
 pub type BrainID = ID<Brain>;
 
+pub const STATS_HISTOGRAM_BINS: usize = 8;
+
 #[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 #[repr(C)]
 pub struct BrainActivityMap {
@@ -62,6 +77,27 @@ pub struct BrainActivityMap {
     pub effectors: Vec<Position>,
     // point
     pub neurons: Vec<Position>,
+    // (point from, point to, receptors)
+    #[serde(default)]
+    pub inhibitory_connections: Vec<(Position, Position, Scalar)>,
+    // (point from, point to, factor)
+    #[serde(default)]
+    pub inhibitory_impulses: Vec<(Position, Position, Scalar)>,
+    // point
+    #[serde(default)]
+    pub inhibitory_neurons: Vec<Position>,
+    // potential of each of `neurons`
+    #[serde(default)]
+    pub neurons_potential: Vec<Scalar>,
+    // potential of each of `inhibitory_neurons`
+    #[serde(default)]
+    pub inhibitory_neurons_potential: Vec<Scalar>,
+    // potential of each of `impulses`
+    #[serde(default)]
+    pub impulses_potential: Vec<Scalar>,
+    // potential of each of `inhibitory_impulses`
+    #[serde(default)]
+    pub inhibitory_impulses_potential: Vec<Scalar>,
 }
 
 #[derive(Debug, Clone)]
@@ -82,6 +118,21 @@ pub struct BrainActivityStats {
     pub outgoing_neuron_connections: Range<usize>,
     // min..max
     pub synapses_receptors: Range<Scalar>,
+    /// Average neuron fires per unit of time.
+    pub mean_firing_rate: Scalar,
+    /// Fraction (0..1) of neurons that fired within `Config::firing_rate_time`.
+    pub active_neurons: Scalar,
+    /// Synapses count in equal bins spanning `synapses_receptors` range.
+    pub synapses_receptors_histogram: [usize; STATS_HISTOGRAM_BINS],
+    /// Neurons count per incoming connections count (last bin holds all higher counts).
+    pub incoming_neuron_connections_histogram: [usize; STATS_HISTOGRAM_BINS],
+    /// Neurons count per outgoing connections count (last bin holds all higher counts).
+    pub outgoing_neuron_connections_histogram: [usize; STATS_HISTOGRAM_BINS],
+    pub strongly_connected_components: usize,
+    /// Average number of synapses on shortest paths from sensors to reachable effectors.
+    pub sensor_effector_path_length: Scalar,
+    /// Number of effectors reachable from any sensor.
+    pub reachable_effectors: usize,
 }
 
 impl Default for BrainActivityStats {
@@ -96,11 +147,28 @@ impl Default for BrainActivityStats {
             incoming_neuron_connections: 0..0,
             outgoing_neuron_connections: 0..0,
             synapses_receptors: 0.0..0.0,
+            mean_firing_rate: 0.0,
+            active_neurons: 0.0,
+            synapses_receptors_histogram: Default::default(),
+            incoming_neuron_connections_histogram: Default::default(),
+            outgoing_neuron_connections_histogram: Default::default(),
+            strongly_connected_components: 0,
+            sensor_effector_path_length: 0.0,
+            reachable_effectors: 0,
         }
     }
 }
 
+/// Stable order of brain inputs and outputs.
+/// Named sensors and effectors go first (sorted by name), then unnamed ones (sorted by ID).
 #[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BrainIoLayout {
+    pub sensors: Vec<SensorID>,
+    pub effectors: Vec<EffectorID>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(from = "BrainData")]
 pub struct Brain {
     id: BrainID,
     neurons: Vec<Neuron>,
@@ -109,6 +177,69 @@ pub struct Brain {
     effectors: Vec<Effector>,
     config: Config,
     new_connections_accum: Scalar,
+    rng: BrainRng,
+    time: Scalar,
+    #[serde(default)]
+    parents: Vec<BrainID>,
+    #[serde(skip)]
+    index: BrainIndex,
+    #[serde(skip)]
+    events: Option<Vec<BrainEvent>>,
+    #[serde(skip)]
+    changes: Option<BrainActivityDelta>,
+}
+
+// serialized form of brain, used to rebuild lookup index after deserialization.
+#[derive(Deserialize)]
+struct BrainData {
+    id: BrainID,
+    neurons: Vec<NeuronData>,
+    synapses: Vec<SynapseData>,
+    sensors: Vec<Sensor>,
+    effectors: Vec<Effector>,
+    config: Config,
+    new_connections_accum: Scalar,
+    #[serde(default)]
+    rng: BrainRng,
+    #[serde(default)]
+    time: Scalar,
+    #[serde(default)]
+    parents: Vec<BrainID>,
+}
+
+impl From<BrainData> for Brain {
+    fn from(data: BrainData) -> Self {
+        let neurons = data
+            .neurons
+            .into_iter()
+            .map(Neuron::from)
+            .collect::<Vec<_>>();
+        let lineages = neurons
+            .iter()
+            .map(|n| (n.id(), n.lineage()))
+            .collect::<HashMap<_, _>>();
+        let synapses = data
+            .synapses
+            .into_iter()
+            .map(|s| s.into_synapse(&lineages))
+            .collect::<Vec<_>>();
+        let index = BrainIndex::build(&neurons, &synapses, &data.sensors, &data.effectors);
+        Self {
+            id: data.id,
+            neurons,
+            synapses,
+            sensors: data.sensors,
+            effectors: data.effectors,
+            config: data.config,
+            new_connections_accum: data.new_connections_accum,
+            rng: data.rng,
+            time: data.time,
+            parents: data.parents,
+            index,
+            events: None,
+            changes: None,
+        }
+    }
 }
 
 impl Brain {
@@ -116,28 +247,57 @@ impl Brain {
         Self::default()
     }
 
+    /// Creates new brain with random number generator seeded with given value.
+    #[inline]
+    pub fn with_seed(seed: u64) -> Self {
+        Self::with_rng(BrainRng::from_u64(seed))
+    }
+
+    /// Creates new brain that will use given random number generator.
+    pub fn with_rng(mut rng: BrainRng) -> Self {
+        Self {
+            id: ID::from_rng(&mut rng),
+            neurons: vec![],
+            synapses: vec![],
+            sensors: vec![],
+            effectors: vec![],
+            config: Default::default(),
+            new_connections_accum: 0.0,
+            time: 0.0,
+            rng,
+            parents: vec![],
+            index: Default::default(),
+            events: None,
+            changes: None,
+        }
+    }
+
+    #[inline]
     pub fn duplicate(&self) -> Self {
-        let id = Default::default();
-        let neuron_indices = iter!(self.neurons).map(|n| n.id()).collect::<Vec<_>>();
-        let neurons = iter!(self.neurons)
-            .map(|n| Neuron::new(id, n.position()))
+        self.duplicate_with_rng(&mut thread_rng())
+    }
+
+    pub fn duplicate_with_rng<R>(&self, rng: &mut R) -> Self
+    where
+        R: Rng,
+    {
+        let mut brain_rng = BrainRng::from_rng_source(rng);
+        let id = ID::from_rng(&mut brain_rng);
+        let neurons = self
+            .neurons
+            .iter()
+            .map(|n| {
+                let mut neuron = Neuron::with_id(ID::from_rng(&mut brain_rng), id, n.position());
+                neuron.set_model(n.model());
+                neuron.set_inhibitory(n.is_inhibitory());
+                neuron.set_lineage(n.lineage());
+                neuron
+            })
             .collect::<Vec<_>>();
         let synapses = iter!(self.synapses)
             .map(|s| {
-                #[cfg(feature = "parallel")]
-                let sindex = neuron_indices
-                    .par_iter()
-                    .position_any(|n| *n == s.source)
-                    .unwrap();
-                #[cfg(not(feature = "parallel"))]
-                let sindex = neuron_indices.iter().position(|n| *n == s.source).unwrap();
-                #[cfg(feature = "parallel")]
-                let nindex = neuron_indices
-                    .par_iter()
-                    .position_any(|n| *n == s.target)
-                    .unwrap();
-                #[cfg(not(feature = "parallel"))]
-                let nindex = neuron_indices.iter().position(|n| *n == s.target).unwrap();
+                let sindex = self.index.neuron(s.source).unwrap();
+                let nindex = self.index.neuron(s.target).unwrap();
                 Synapse {
                     source: neurons[sindex].id(),
                     target: neurons[nindex].id(),
@@ -145,40 +305,29 @@ impl Brain {
                     receptors: s.receptors,
                     impulses: vec![],
                     inactivity: 0.0,
+                    inhibitory: s.inhibitory,
+                    eligibility: 0.0,
+                    lineage: s.lineage,
                 }
             })
             .collect::<Vec<_>>();
         let sensors = iter!(self.sensors)
             .map(|s| {
-                #[cfg(feature = "parallel")]
-                let index = neuron_indices
-                    .par_iter()
-                    .position_any(|n| *n == s.target)
-                    .unwrap();
-                #[cfg(not(feature = "parallel"))]
-                let index = neuron_indices.iter().position(|n| *n == s.target).unwrap();
-                Sensor {
-                    id: s.id,
-                    target: neurons[index].id(),
-                }
+                let index = self.index.neuron(s.target).unwrap();
+                let mut sensor = Sensor::new(s.id, neurons[index].id(), s.mode);
+                sensor.label = s.label.clone();
+                sensor
             })
             .collect::<Vec<_>>();
         let effectors = iter!(self.effectors)
             .map(|e| {
-                #[cfg(feature = "parallel")]
-                let index = neuron_indices
-                    .par_iter()
-                    .position_any(|n| *n == e.source)
-                    .unwrap();
-                #[cfg(not(feature = "parallel"))]
-                let index = neuron_indices.iter().position(|n| *n == e.source).unwrap();
-                Effector {
-                    id: e.id,
-                    source: neurons[index].id(),
-                    potential: 0.0,
-                }
+                let index = self.index.neuron(e.source).unwrap();
+                let mut effector = Effector::new(e.id, neurons[index].id(), e.mode);
+                effector.label = e.label.clone();
+                effector
             })
             .collect::<Vec<_>>();
+        let index = BrainIndex::build(&neurons, &synapses, &sensors, &effectors);
         Self {
             id,
             neurons,
@@ -187,14 +336,28 @@ impl Brain {
             effectors,
             config: self.config.clone(),
             new_connections_accum: 0.0,
+            time: 0.0,
+            rng: brain_rng,
+            parents: vec![self.id],
+            index,
+            events: None,
+            changes: None,
         }
     }
 
+    #[inline]
     pub fn merge(&self, other: &Self) -> Self {
-        let mut rng = thread_rng();
-        let id = Default::default();
-        let brain_a = self.duplicate();
-        let brain_b = other.duplicate();
+        self.merge_with_rng(other, &mut thread_rng())
+    }
+
+    pub fn merge_with_rng<R>(&self, other: &Self, rng: &mut R) -> Self
+    where
+        R: Rng,
+    {
+        let mut brain_rng = BrainRng::from_rng_source(rng);
+        let id = ID::from_rng(&mut brain_rng);
+        let brain_a = self.duplicate_with_rng(rng);
+        let brain_b = other.duplicate_with_rng(rng);
         let neurons_count = (brain_a.neurons.len() + brain_b.neurons.len()) / 2;
         let synapses_count = (brain_a.synapses.len() + brain_b.synapses.len()) / 2;
         let sensors_count = (brain_a.sensors.len() + brain_b.sensors.len()) / 2;
@@ -203,26 +366,24 @@ impl Brain {
             .neurons
             .iter()
             .chain(brain_b.neurons.iter())
-            .map(|n| Neuron::with_id(n.id(), id, n.position()))
-            .collect();
+            .map(|n| {
+                let mut neuron = Neuron::with_id(n.id(), id, n.position());
+                neuron.set_model(n.model());
+                neuron.set_inhibitory(n.is_inhibitory());
+                neuron.set_lineage(n.lineage());
+                neuron
+            })
+            .collect::<Vec<_>>();
         let synapses = brain_a
             .synapses
             .iter()
             .chain(brain_b.synapses.iter())
             .cloned()
-            .collect();
-        let sensors = brain_a
-            .sensors
-            .iter()
-            .chain(brain_b.sensors.iter())
-            .cloned()
-            .collect();
-        let effectors = brain_a
-            .effectors
-            .iter()
-            .chain(brain_b.effectors.iter())
-            .cloned()
-            .collect();
+            .collect::<Vec<_>>();
+        // parents of the same lineage share sensor and effector IDs, so pick one of each pair.
+        let sensors = merge_unique(&brain_a.sensors, &brain_b.sensors, |s| s.id, rng);
+        let effectors = merge_unique(&brain_a.effectors, &brain_b.effectors, |e| e.id, rng);
+        let index = BrainIndex::build(&neurons, &synapses, &sensors, &effectors);
         let mut brain = Self {
             id,
             neurons,
@@ -231,6 +392,12 @@ impl Brain {
             effectors,
             config: brain_a.config().merge(brain_b.config()),
             new_connections_accum: 0.0,
+            time: 0.0,
+            rng: brain_rng,
+            parents: vec![self.id, other.id],
+            index,
+            events: None,
+            changes: None,
         };
         while brain.neurons.len() > neurons_count {
             if brain
@@ -268,11 +435,166 @@ impl Brain {
         brain
     }
 
+    #[inline]
+    pub fn crossover(&self, other: &Self, homology_range: Scalar) -> Self {
+        self.crossover_with_rng(other, homology_range, &mut thread_rng())
+    }
+
+    /// Crosses over this (fitter) brain with other one, keeping structure of this brain.
+    /// Homologous neurons and synapses (see `compatibility::align_neurons`) inherit their
+    /// properties from randomly chosen parent, other genes come from this brain.
+    pub fn crossover_with_rng<R>(&self, other: &Self, homology_range: Scalar, rng: &mut R) -> Self
+    where
+        R: Rng,
+    {
+        let mut brain_rng = BrainRng::from_rng_source(rng);
+        let id = ID::from_rng(&mut brain_rng);
+        let homologous = align_neurons(self, other, homology_range)
+            .into_iter()
+            .collect::<HashMap<_, _>>();
+        let neurons = self
+            .neurons
+            .iter()
+            .map(|n| {
+                let source = match homologous.get(&n.id()) {
+                    Some(other_id) if rng.gen::<bool>() => other.neuron(*other_id).unwrap(),
+                    _ => n,
+                };
+                let mut neuron = Neuron::with_id(ID::from_rng(&mut brain_rng), id, n.position());
+                neuron.set_model(source.model());
+                neuron.set_inhibitory(source.is_inhibitory());
+                neuron.set_lineage(n.lineage());
+                neuron
+            })
+            .collect::<Vec<_>>();
+        let synapses = self
+            .synapses
+            .iter()
+            .map(|s| {
+                let other_index = match (homologous.get(&s.source), homologous.get(&s.target)) {
+                    (Some(source), Some(target)) => other.index.synapse(*source, *target),
+                    _ => None,
+                };
+                let source = match other_index {
+                    Some(index) if rng.gen::<bool>() => &other.synapses[index],
+                    _ => s,
+                };
+                Synapse {
+                    source: neurons[self.index.neuron(s.source).unwrap()].id(),
+                    target: neurons[self.index.neuron(s.target).unwrap()].id(),
+                    distance: source.distance,
+                    receptors: source.receptors,
+                    impulses: vec![],
+                    inactivity: 0.0,
+                    inhibitory: source.inhibitory,
+                    eligibility: 0.0,
+                    lineage: s.lineage,
+                }
+            })
+            .collect::<Vec<_>>();
+        let sensors = self
+            .sensors
+            .iter()
+            .map(|s| {
+                let index = self.index.neuron(s.target).unwrap();
+                let mut sensor = Sensor::new(s.id, neurons[index].id(), s.mode);
+                sensor.label = s.label.clone();
+                sensor
+            })
+            .collect::<Vec<_>>();
+        let effectors = self
+            .effectors
+            .iter()
+            .map(|e| {
+                let index = self.index.neuron(e.source).unwrap();
+                let mut effector = Effector::new(e.id, neurons[index].id(), e.mode);
+                effector.label = e.label.clone();
+                effector
+            })
+            .collect::<Vec<_>>();
+        let index = BrainIndex::build(&neurons, &synapses, &sensors, &effectors);
+        Self {
+            id,
+            neurons,
+            synapses,
+            sensors,
+            effectors,
+            config: self.config.merge(&other.config),
+            new_connections_accum: 0.0,
+            time: 0.0,
+            rng: brain_rng,
+            parents: vec![self.id, other.id],
+            index,
+            events: None,
+            changes: None,
+        }
+    }
+
     #[inline]
     pub fn id(&self) -> BrainID {
         self.id
     }
 
+    /// Brains this one was made from: none for brain made from scratch, one for duplicated
+    /// (mutated) brain and two for merged or crossed over brain.
+    #[inline]
+    pub fn parents(&self) -> &[BrainID] {
+        &self.parents
+    }
+
+    /// Total time this brain was processed for.
+    #[inline]
+    pub fn time(&self) -> Scalar {
+        self.time
+    }
+
+    /// Enables or disables collecting events (disabled by default).
+    /// Disabling drops events that were not drained yet.
+    pub fn set_events_enabled(&mut self, enabled: bool) {
+        if enabled {
+            if self.events.is_none() {
+                self.events = Some(vec![]);
+            }
+        } else {
+            self.events = None;
+        }
+    }
+
+    #[inline]
+    pub fn events_enabled(&self) -> bool {
+        self.events.is_some()
+    }
+
+    /// Takes all events collected since last call.
+    pub fn drain_events(&mut self) -> Vec<BrainEvent> {
+        match &mut self.events {
+            Some(events) => std::mem::take(events),
+            None => vec![],
+        }
+    }
+
+    #[inline]
+    fn emit(&mut self, event: BrainEvent) {
+        if let Some(events) = &mut self.events {
+            events.push(event);
+        }
+    }
+
+    #[inline]
+    fn track<F>(&mut self, f: F)
+    where
+        F: FnOnce(&mut BrainActivityDelta),
+    {
+        if let Some(changes) = &mut self.changes {
+            f(changes);
+        }
+    }
+
+    #[inline]
+    pub(crate) fn index(&self) -> &BrainIndex {
+        &self.index
+    }
+
     #[inline]
     pub fn get_neurons(&self) -> Vec<NeuronID> {
         iter!(self.neurons).map(|n| n.id()).collect()
@@ -315,11 +637,37 @@ impl Brain {
         self.get_neurons_potential() + self.get_impulses_potential()
     }
 
+    /// Removes all neurons together with their synapses, sensors and effectors.
+    /// Emits the same events as killing every neuron would.
     pub fn clear(&mut self) {
+        if let Some(events) = &mut self.events {
+            events.extend(self.sensors.iter().map(|s| BrainEvent::SensorRemoved(s.id)));
+            events.extend(
+                self.effectors
+                    .iter()
+                    .map(|e| BrainEvent::EffectorRemoved(e.id)),
+            );
+            events.extend(self.neurons.iter().map(|n| BrainEvent::NeuronDied(n.id())));
+        }
+        if let Some(changes) = &mut self.changes {
+            for synapse in &self.synapses {
+                changes.synapse_removed(synapse.source, synapse.target);
+            }
+            for sensor in &self.sensors {
+                changes.sensor_removed(sensor.id);
+            }
+            for effector in &self.effectors {
+                changes.effector_removed(effector.id);
+            }
+            for neuron in &self.neurons {
+                changes.neuron_removed(neuron.id());
+            }
+        }
         self.neurons.clear();
         self.synapses.clear();
         self.sensors.clear();
         self.effectors.clear();
+        self.index.clear();
     }
 
     #[inline]
@@ -337,27 +685,32 @@ impl Brain {
         self.config = config;
     }
 
+    #[inline]
+    pub fn rng(&self) -> &BrainRng {
+        &self.rng
+    }
+
+    #[inline]
+    pub fn rng_mut(&mut self) -> &mut BrainRng {
+        &mut self.rng
+    }
+
+    #[inline]
+    pub fn reseed(&mut self, seed: u64) {
+        self.rng = BrainRng::from_u64(seed);
+    }
+
     #[inline]
     pub fn neuron(&self, id: NeuronID) -> Option<&Neuron> {
-        #[cfg(feature = "parallel")]
-        {
-            self.neurons.par_iter().find_any(|n| n.id() == id)
-        }
-        #[cfg(not(feature = "parallel"))]
-        {
-            self.neurons.iter().find(|n| n.id() == id)
-        }
+        self.index.neuron(id).map(|index| &self.neurons[index])
     }
 
     #[inline]
     pub fn neuron_mut(&mut self, id: NeuronID) -> Option<&mut Neuron> {
-        #[cfg(feature = "parallel")]
-        {
-            self.neurons.par_iter_mut().find_any(|n| n.id() == id)
-        }
-        #[cfg(not(feature = "parallel"))]
-        {
-            self.neurons.iter_mut().find(|n| n.id() == id)
+        if let Some(index) = self.index.neuron(id) {
+            Some(&mut self.neurons[index])
+        } else {
+            None
         }
     }
 
@@ -366,68 +719,58 @@ impl Brain {
         &self.neurons
     }
 
+    #[inline]
+    pub fn synapses(&self) -> &[Synapse] {
+        &self.synapses
+    }
+
+    #[inline]
+    pub(crate) fn synapses_mut(&mut self) -> &mut [Synapse] {
+        &mut self.synapses
+    }
+
     #[inline]
     pub fn are_neurons_connected(&self, from: NeuronID, to: NeuronID) -> bool {
-        iter!(self.synapses).any(|s| s.source == from && s.target == to)
+        self.index.synapse(from, to).is_some()
     }
 
     #[inline]
     pub fn does_neuron_has_connections(&self, id: NeuronID) -> bool {
-        iter!(self.synapses).any(|s| s.source == id || s.target == id)
+        self.index.has_connections(id)
     }
 
     #[inline]
     pub fn get_neuron_connections_count(&self, id: NeuronID) -> (usize, usize) {
-        let incoming = iter!(self.synapses).filter(|s| s.target == id).count();
-        let outgoing = iter!(self.synapses).filter(|s| s.source == id).count();
-        (incoming, outgoing)
+        (self.index.incoming(id).len(), self.index.outgoing(id).len())
     }
 
     #[inline]
     pub fn get_neuron_connections(&self, id: NeuronID) -> (Vec<NeuronID>, Vec<NeuronID>) {
-        let incoming = iter!(self.synapses)
-            .filter_map(|s| if s.target == id { Some(s.source) } else { None })
-            .collect();
-        let outgoing = iter!(self.synapses)
-            .filter_map(|s| if s.source == id { Some(s.target) } else { None })
-            .collect();
-        (incoming, outgoing)
+        (
+            self.index.incoming(id).to_vec(),
+            self.index.outgoing(id).to_vec(),
+        )
     }
 
     pub fn create_sensor(&mut self, target: NeuronID) -> Result<SensorID> {
-        #[cfg(feature = "parallel")]
-        let sensor = self.sensors.par_iter().find_any(|s| s.target == target);
-        #[cfg(not(feature = "parallel"))]
-        let sensor = self.sensors.iter().find(|s| s.target == target);
-        if let Some(sensor) = sensor {
-            return Err(Error::NeuronIsAlreadyConnectedToSensor(target, sensor.id));
+        if let Some(sensor) = self.index.neuron_sensor(target) {
+            return Err(Error::NeuronIsAlreadyConnectedToSensor(target, sensor));
         }
-        #[cfg(feature = "parallel")]
-        let effector = self.effectors.par_iter().find_any(|e| e.source == target);
-        #[cfg(not(feature = "parallel"))]
-        let effector = self.effectors.iter().find(|e| e.source == target);
-        if let Some(effector) = effector {
-            return Err(Error::NeuronIsAlreadyConnectedToEffector(
-                target,
-                effector.id,
-            ));
-        }
-        let sensor = Sensor {
-            id: Default::default(),
-            target,
-        };
+        if let Some(effector) = self.index.neuron_effector(target) {
+            return Err(Error::NeuronIsAlreadyConnectedToEffector(target, effector));
+        }
+        let sensor = Sensor::new(ID::from_rng(&mut self.rng), target, Default::default());
         let id = sensor.id;
-        self.sensors.push(sensor);
+        self.index.push_sensor(&mut self.sensors, sensor);
+        self.track(|c| c.sensor_added(id, target));
         Ok(id)
     }
 
     pub fn kill_sensor(&mut self, id: SensorID) -> Result<()> {
-        #[cfg(feature = "parallel")]
-        let index = self.sensors.par_iter().position_any(|s| s.id == id);
-        #[cfg(not(feature = "parallel"))]
-        let index = self.sensors.iter().position(|s| s.id == id);
-        if let Some(index) = index {
-            self.sensors.swap_remove(index);
+        if let Some(index) = self.index.sensor(id) {
+            self.index.swap_remove_sensor(&mut self.sensors, index);
+            self.track(|c| c.sensor_removed(id));
+            self.emit(BrainEvent::SensorRemoved(id));
             Ok(())
         } else {
             Err(Error::SensorDoesNotExists(id))
@@ -435,125 +778,306 @@ impl Brain {
     }
 
     pub fn sensor_trigger_impulse(&mut self, id: SensorID, potential: Scalar) -> Result<()> {
-        #[cfg(feature = "parallel")]
-        let sensor = self.sensors.par_iter().find_any(|s| s.id == id);
-        #[cfg(not(feature = "parallel"))]
-        let sensor = self.sensors.iter().find(|s| s.id == id);
-        if let Some(sensor) = sensor {
-            #[cfg(feature = "parallel")]
-            let neuron = self
-                .neurons
-                .par_iter_mut()
-                .find_any(|n| n.id() == sensor.target);
-            #[cfg(not(feature = "parallel"))]
-            let neuron = self.neurons.iter_mut().find(|n| n.id() == sensor.target);
-            if let Some(neuron) = neuron {
-                neuron.push_potential(potential);
+        if let Some(index) = self.index.sensor(id) {
+            let target = self.sensors[index].target;
+            let models = self.config.neuron_models();
+            if let Some(neuron) = self.neuron_mut(target) {
+                neuron.receive(models.get(neuron.model()), potential);
                 Ok(())
             } else {
-                Err(Error::NeuronDoesNotExists(sensor.target))
+                Err(Error::NeuronDoesNotExists(target))
             }
         } else {
             Err(Error::SensorDoesNotExists(id))
         }
     }
 
+    /// Sets analog input of sensor, used by all sensor modes except `SensorMode::Impulse`.
+    pub fn sensor_set_value(&mut self, id: SensorID, value: Scalar) -> Result<()> {
+        if let Some(index) = self.index.sensor(id) {
+            self.sensors[index].value = value;
+            Ok(())
+        } else {
+            Err(Error::SensorDoesNotExists(id))
+        }
+    }
+
+    pub fn set_sensor_mode(&mut self, id: SensorID, mode: SensorMode) -> Result<()> {
+        if let Some(index) = self.index.sensor(id) {
+            let sensor = &mut self.sensors[index];
+            sensor.mode = mode;
+            sensor.phase = 0.0;
+            Ok(())
+        } else {
+            Err(Error::SensorDoesNotExists(id))
+        }
+    }
+
+    #[inline]
+    pub fn sensor(&self, id: SensorID) -> Option<&Sensor> {
+        self.index.sensor(id).map(|index| &self.sensors[index])
+    }
+
+    pub fn set_sensor_label(&mut self, id: SensorID, label: Label) -> Result<()> {
+        if let Some(index) = self.index.sensor(id) {
+            self.sensors[index].label = label;
+            Ok(())
+        } else {
+            Err(Error::SensorDoesNotExists(id))
+        }
+    }
+
+    pub fn sensor_by_name(&self, name: &str) -> Option<SensorID> {
+        self.sensors
+            .iter()
+            .find(|s| s.label.has_name(name))
+            .map(|s| s.id)
+    }
+
+    pub fn sensors_by_tag(&self, tag: &str) -> Vec<SensorID> {
+        self.sensors
+            .iter()
+            .filter(|s| s.label.has_tag(tag))
+            .map(|s| s.id)
+            .collect()
+    }
+
     pub fn create_effector(&mut self, source: NeuronID) -> Result<EffectorID> {
-        #[cfg(feature = "parallel")]
-        let sensor = self.sensors.par_iter().find_any(|s| s.target == source);
-        #[cfg(not(feature = "parallel"))]
-        let sensor = self.sensors.iter().find(|s| s.target == source);
-        if let Some(sensor) = sensor {
-            return Err(Error::NeuronIsAlreadyConnectedToSensor(source, sensor.id));
+        if let Some(sensor) = self.index.neuron_sensor(source) {
+            return Err(Error::NeuronIsAlreadyConnectedToSensor(source, sensor));
         }
-        #[cfg(feature = "parallel")]
-        let effector = self.effectors.par_iter().find_any(|e| e.source == source);
-        #[cfg(not(feature = "parallel"))]
-        let effector = self.effectors.iter().find(|e| e.source == source);
-        if let Some(effector) = effector {
-            return Err(Error::NeuronIsAlreadyConnectedToEffector(
-                source,
-                effector.id,
-            ));
-        }
-        let effector = Effector {
-            id: Default::default(),
-            source,
-            potential: 0.0,
-        };
+        if let Some(effector) = self.index.neuron_effector(source) {
+            return Err(Error::NeuronIsAlreadyConnectedToEffector(source, effector));
+        }
+        let effector = Effector::new(ID::from_rng(&mut self.rng), source, Default::default());
         let id = effector.id;
-        self.effectors.push(effector);
+        self.index.push_effector(&mut self.effectors, effector);
+        self.track(|c| c.effector_added(id, source));
         Ok(id)
     }
 
     pub fn kill_effector(&mut self, id: EffectorID) -> Result<()> {
-        #[cfg(feature = "parallel")]
-        let index = self.effectors.par_iter().position_any(|e| e.id == id);
-        #[cfg(not(feature = "parallel"))]
-        let index = self.effectors.iter().position(|e| e.id == id);
-        if let Some(index) = index {
-            self.effectors.swap_remove(index);
+        if let Some(index) = self.index.effector(id) {
+            self.index.swap_remove_effector(&mut self.effectors, index);
+            self.track(|c| c.effector_removed(id));
+            self.emit(BrainEvent::EffectorRemoved(id));
+            Ok(())
+        } else {
+            Err(Error::EffectorDoesNotExists(id))
+        }
+    }
+
+    /// Reads current effector output without resetting it.
+    pub fn effector_potential(&self, id: EffectorID) -> Result<Scalar> {
+        if let Some(index) = self.index.effector(id) {
+            Ok(self.effectors[index].potential)
+        } else {
+            Err(Error::EffectorDoesNotExists(id))
+        }
+    }
+
+    pub fn set_effector_mode(&mut self, id: EffectorID, mode: EffectorMode) -> Result<()> {
+        if let Some(index) = self.index.effector(id) {
+            let effector = &mut self.effectors[index];
+            effector.mode = mode;
+            effector.reset();
+            Ok(())
+        } else {
+            Err(Error::EffectorDoesNotExists(id))
+        }
+    }
+
+    #[inline]
+    pub fn effector(&self, id: EffectorID) -> Option<&Effector> {
+        self.index.effector(id).map(|index| &self.effectors[index])
+    }
+
+    pub fn set_effector_label(&mut self, id: EffectorID, label: Label) -> Result<()> {
+        if let Some(index) = self.index.effector(id) {
+            self.effectors[index].label = label;
             Ok(())
         } else {
             Err(Error::EffectorDoesNotExists(id))
         }
     }
 
+    pub fn effector_by_name(&self, name: &str) -> Option<EffectorID> {
+        self.effectors
+            .iter()
+            .find(|e| e.label.has_name(name))
+            .map(|e| e.id)
+    }
+
+    pub fn effectors_by_tag(&self, tag: &str) -> Vec<EffectorID> {
+        self.effectors
+            .iter()
+            .filter(|e| e.label.has_tag(tag))
+            .map(|e| e.id)
+            .collect()
+    }
+
+    pub fn io_layout(&self) -> BrainIoLayout {
+        BrainIoLayout {
+            sensors: io_order(&self.sensors, |s| (s.id, &s.label)),
+            effectors: io_order(&self.effectors, |e| (e.id, &e.label)),
+        }
+    }
+
+    /// Feeds sensors with inputs ordered by `io_layout`.
+    /// Sensors in `SensorMode::Impulse` mode get triggered, others get their value set.
+    pub fn feed(&mut self, inputs: &[Scalar]) -> Result<()> {
+        let sensors = io_order(&self.sensors, |s| (s.id, &s.label));
+        if inputs.len() != sensors.len() {
+            return Err(Error::InputsCountMismatch(sensors.len(), inputs.len()));
+        }
+        for (id, value) in sensors.into_iter().zip(inputs.iter()) {
+            let index = self.index.sensor(id).unwrap();
+            if self.sensors[index].mode == SensorMode::Impulse {
+                self.sensor_trigger_impulse(id, *value)?;
+            } else {
+                self.sensors[index].value = *value;
+            }
+        }
+        Ok(())
+    }
+
+    /// Releases effectors potential into outputs ordered by `io_layout`.
+    /// Works the same as `effector_potential_release`, so continuous modes keep their state.
+    pub fn read_outputs(&mut self, outputs: &mut [Scalar]) -> Result<()> {
+        let effectors = io_order(&self.effectors, |e| (e.id, &e.label));
+        if outputs.len() != effectors.len() {
+            return Err(Error::OutputsCountMismatch(effectors.len(), outputs.len()));
+        }
+        for (id, value) in effectors.into_iter().zip(outputs.iter_mut()) {
+            *value = self.effector_potential_release(id)?;
+        }
+        Ok(())
+    }
+
+    /// Reads effector output and clears it for `EffectorMode::Potential` effectors.
+    /// Other modes keep their state, so their output stays continuous across reads.
     pub fn effector_potential_release(&mut self, id: EffectorID) -> Result<Scalar> {
-        #[cfg(feature = "parallel")]
-        let effector = self.effectors.par_iter_mut().find_any(|e| e.id == id);
-        #[cfg(not(feature = "parallel"))]
-        let effector = self.effectors.iter_mut().find(|e| e.id == id);
-        if let Some(effector) = effector {
-            let potential = effector.potential;
-            effector.potential = 0.0;
-            Ok(potential)
+        if let Some(index) = self.index.effector(id) {
+            Ok(self.effectors[index].release())
         } else {
             Err(Error::EffectorDoesNotExists(id))
         }
     }
 
     pub fn create_neuron(&mut self, position: Position) -> NeuronID {
-        let neuron = Neuron::new(self.id, position);
+        let neuron = Neuron::with_id(ID::from_rng(&mut self.rng), self.id, position);
         let id = neuron.id();
-        self.neurons.push(neuron);
+        self.index.push_neuron(&mut self.neurons, neuron);
+        self.track(|c| c.neuron_added(id, position, false));
         id
     }
 
-    pub fn kill_neuron(&mut self, id: NeuronID) -> Result<()> {
-        #[cfg(feature = "parallel")]
-        let index = self.neurons.par_iter().position_any(|n| n.id() == id);
-        #[cfg(not(feature = "parallel"))]
-        let index = self.neurons.iter().position(|n| n.id() == id);
-        if let Some(index) = index {
-            self.neurons.swap_remove(index);
-            while let Some(index) = self
-                .synapses
-                .iter()
-                .position(|s| s.source == id || s.target == id)
-            {
-                self.synapses.swap_remove(index);
-            }
-            #[cfg(feature = "parallel")]
-            let index = self.sensors.par_iter().position_any(|s| s.target == id);
-            #[cfg(not(feature = "parallel"))]
-            let index = self.sensors.iter().position(|s| s.target == id);
-            while let Some(index) = index {
-                self.sensors.swap_remove(index);
+    /// Overrides neuron model of given neuron (`None` uses model from brain config).
+    pub fn set_neuron_model(&mut self, id: NeuronID, model: Option<NeuronModelType>) -> Result<()> {
+        if let Some(neuron) = self.neuron_mut(id) {
+            neuron.set_model(model);
+            Ok(())
+        } else {
+            Err(Error::NeuronDoesNotExists(id))
+        }
+    }
+
+    /// Marks neuron and all its outgoing synapses as inhibitory or excitatory.
+    pub fn set_neuron_inhibitory(&mut self, id: NeuronID, value: bool) -> Result<()> {
+        if let Some(neuron) = self.neuron_mut(id) {
+            neuron.set_inhibitory(value);
+            for to in self.index.outgoing(id) {
+                if let Some(index) = self.index.synapse(id, *to) {
+                    self.synapses[index].inhibitory = value;
+                }
             }
-            #[cfg(feature = "parallel")]
-            let index = self.effectors.par_iter().position_any(|e| e.source == id);
-            #[cfg(not(feature = "parallel"))]
-            let index = self.effectors.iter().position(|e| e.source == id);
-            while let Some(index) = index {
-                self.effectors.swap_remove(index);
+            self.track(|c| c.neuron_inhibitory_changed(id, value));
+            Ok(())
+        } else {
+            Err(Error::NeuronDoesNotExists(id))
+        }
+    }
+
+    /// Moves neuron and updates length of all its synapses.
+    pub fn set_neuron_position(&mut self, id: NeuronID, position: Position) -> Result<()> {
+        if let Some(neuron) = self.neuron_mut(id) {
+            neuron.set_position(position);
+            let connected = self
+                .index
+                .incoming(id)
+                .iter()
+                .map(|other| (*other, id))
+                .chain(self.index.outgoing(id).iter().map(|other| (id, *other)))
+                .collect::<Vec<_>>();
+            for (from, to) in connected {
+                if let Some(index) = self.index.synapse(from, to) {
+                    let source = self.neuron(from).unwrap().position();
+                    let target = self.neuron(to).unwrap().position();
+                    self.synapses[index].distance = source.distance(target);
+                }
             }
+            self.track(|c| c.neuron_moved(id, position));
+            Ok(())
+        } else {
+            Err(Error::NeuronDoesNotExists(id))
+        }
+    }
+
+    /// Removes neurons that no sensor can reach, together with their synapses and effectors.
+    /// Brain without sensors ends up empty. Returns number of removed neurons.
+    pub fn prune_unreachable(&mut self) -> usize {
+        let neurons = graph::unreachable_neurons(self);
+        for id in &neurons {
+            drop(self.kill_neuron(*id));
+        }
+        neurons.len()
+    }
+
+    pub fn kill_neuron(&mut self, id: NeuronID) -> Result<()> {
+        if let Some(index) = self.index.neuron(id) {
+            self.remove_neuron_at(index);
             Ok(())
         } else {
             Err(Error::NeuronDoesNotExists(id))
         }
     }
 
+    // removes neuron stored at given index together with all its synapses, sensor and effector.
+    fn remove_neuron_at(&mut self, index: usize) -> Neuron {
+        let neuron = self.index.swap_remove_neuron(&mut self.neurons, index);
+        let id = neuron.id();
+        let connected = self
+            .index
+            .incoming(id)
+            .iter()
+            .map(|other| (*other, id))
+            .chain(self.index.outgoing(id).iter().map(|other| (id, *other)))
+            .collect::<Vec<_>>();
+        for (from, to) in connected {
+            if let Some(index) = self.index.synapse(from, to) {
+                self.index.swap_remove_synapse(&mut self.synapses, index);
+                self.track(|c| c.synapse_removed(from, to));
+            }
+        }
+        if let Some(sensor) = self.index.neuron_sensor(id) {
+            if let Some(index) = self.index.sensor(sensor) {
+                self.index.swap_remove_sensor(&mut self.sensors, index);
+                self.track(|c| c.sensor_removed(sensor));
+                self.emit(BrainEvent::SensorRemoved(sensor));
+            }
+        }
+        if let Some(effector) = self.index.neuron_effector(id) {
+            if let Some(index) = self.index.effector(effector) {
+                self.index.swap_remove_effector(&mut self.effectors, index);
+                self.track(|c| c.effector_removed(effector));
+                self.emit(BrainEvent::EffectorRemoved(effector));
+            }
+        }
+        self.track(|c| c.neuron_removed(id));
+        self.emit(BrainEvent::NeuronDied(id));
+        neuron
+    }
+
     pub fn bind_neurons(&mut self, from: NeuronID, to: NeuronID) -> Result<Option<Scalar>> {
         if from == to {
             return Err(Error::BindingNeuronToItSelf(from));
@@ -563,33 +1087,36 @@ impl Brain {
                 if self.are_neurons_connected(from, to) {
                     return Ok(None);
                 }
-                #[cfg(feature = "parallel")]
-                let sensor = self.sensors.par_iter().find_any(|s| s.target == to);
-                #[cfg(not(feature = "parallel"))]
-                let sensor = self.sensors.iter().find(|s| s.target == to);
-                if let Some(sensor) = sensor {
-                    return Err(Error::BindingNeuronToSensor(to, sensor.id));
+                if let Some(sensor) = self.index.neuron_sensor(to) {
+                    return Err(Error::BindingNeuronToSensor(to, sensor));
                 }
-                #[cfg(feature = "parallel")]
-                let effector = self.effectors.par_iter().find_any(|e| e.source == from);
-                #[cfg(not(feature = "parallel"))]
-                let effector = self.effectors.iter().find(|e| e.source == from);
-                if let Some(effector) = effector {
-                    return Err(Error::BindingEffectorToNeuron(effector.id, from));
+                if let Some(effector) = self.index.neuron_effector(from) {
+                    return Err(Error::BindingEffectorToNeuron(effector, from));
                 }
                 let distance = source.position().distance(target.position());
-                let receptors = thread_rng().gen_range(
+                let inhibitory = source.is_inhibitory();
+                let source_lineage = source.lineage();
+                let target_lineage = target.lineage();
+                let receptors = self.rng.gen_range(
                     self.config.default_receptors.start,
                     self.config.default_receptors.end,
                 );
-                self.synapses.push(Synapse {
-                    source: from,
-                    target: to,
-                    distance,
-                    receptors,
-                    impulses: vec![],
-                    inactivity: 0.0,
-                });
+                self.index.push_synapse(
+                    &mut self.synapses,
+                    Synapse {
+                        source: from,
+                        target: to,
+                        distance,
+                        receptors,
+                        impulses: vec![],
+                        inactivity: 0.0,
+                        inhibitory,
+                        eligibility: 0.0,
+                        lineage: LineageID::from_synapse(source_lineage, target_lineage),
+                    },
+                );
+                self.track(|c| c.synapse_added(from, to, inhibitory));
+                self.emit(BrainEvent::SynapseCreated(from, to));
                 Ok(Some(receptors))
             } else {
                 Err(Error::NeuronDoesNotExists(to))
@@ -603,20 +1130,11 @@ impl Brain {
         if from == to {
             return Err(Error::UnbindingNeuronFromItSelf(from));
         }
-        if iter!(self.neurons).any(|n| n.id() == from) {
-            if iter!(self.neurons).any(|n| n.id() == to) {
-                #[cfg(feature = "parallel")]
-                let index = self
-                    .synapses
-                    .par_iter()
-                    .position_any(|s| s.source == from && s.target == to);
-                #[cfg(not(feature = "parallel"))]
-                let index = self
-                    .synapses
-                    .iter()
-                    .position(|s| s.source == from && s.target == to);
-                if let Some(index) = index {
-                    self.synapses.swap_remove(index);
+        if self.index.neuron(from).is_some() {
+            if self.index.neuron(to).is_some() {
+                if let Some(index) = self.index.synapse(from, to) {
+                    self.index.swap_remove_synapse(&mut self.synapses, index);
+                    self.track(|c| c.synapse_removed(from, to));
                     Ok(true)
                 } else {
                     Ok(false)
@@ -637,70 +1155,187 @@ impl Brain {
             synapse.impulses.len();
         }
         for effector in &mut self.effectors {
-            effector.potential = 0.0;
+            effector.reset();
         }
     }
 
+    /// Checks brain integrity and reports first broken invariant.
+    /// Useful for brains loaded from snapshots that could be corrupted.
+    pub fn validate(&self) -> Result<()> {
+        let mut neurons = HashSet::with_capacity(self.neurons.len());
+        for neuron in &self.neurons {
+            let id = neuron.id();
+            if !neurons.insert(id) {
+                return Err(Error::DuplicatedNeuron(id));
+            }
+            if neuron.owner_id() != self.id {
+                return Err(Error::NeuronHasInvalidOwner(id, neuron.owner_id()));
+            }
+            if !neuron.potential().is_finite() {
+                return Err(Error::NeuronHasInvalidPotential(id));
+            }
+            let state = neuron.state();
+            if !state.recovery.is_finite() || !state.refractory.is_finite() {
+                return Err(Error::NeuronHasInvalidState(id));
+            }
+        }
+        let mut synapses = HashSet::with_capacity(self.synapses.len());
+        for synapse in &self.synapses {
+            let (from, to) = (synapse.source, synapse.target);
+            if from == to {
+                return Err(Error::SynapseBindsNeuronToItSelf(from));
+            }
+            if !neurons.contains(&from) || !neurons.contains(&to) {
+                return Err(Error::SynapseNeuronDoesNotExists(from, to));
+            }
+            if !synapses.insert((from, to)) {
+                return Err(Error::DuplicatedSynapse(from, to));
+            }
+            if !synapse.receptors.is_finite()
+                || !synapse.distance.is_finite()
+                || !synapse.inactivity.is_finite()
+                || synapse
+                    .impulses
+                    .iter()
+                    .any(|i| !i.potential.is_finite() || !i.timeout.is_finite())
+            {
+                return Err(Error::SynapseHasInvalidValues(from, to));
+            }
+        }
+        let mut sensors = HashSet::with_capacity(self.sensors.len());
+        let mut sensor_targets = HashSet::with_capacity(self.sensors.len());
+        for sensor in &self.sensors {
+            if !sensors.insert(sensor.id) {
+                return Err(Error::DuplicatedSensor(sensor.id));
+            }
+            if !neurons.contains(&sensor.target) {
+                return Err(Error::SensorTargetDoesNotExists(sensor.id, sensor.target));
+            }
+            if !sensor_targets.insert(sensor.target) {
+                return Err(Error::NeuronHasMultipleSensors(sensor.target));
+            }
+            if !sensor.value.is_finite() || !sensor.phase.is_finite() {
+                return Err(Error::SensorHasInvalidState(sensor.id));
+            }
+        }
+        let mut effectors = HashSet::with_capacity(self.effectors.len());
+        let mut effector_sources = HashSet::with_capacity(self.effectors.len());
+        for effector in &self.effectors {
+            if !effectors.insert(effector.id) {
+                return Err(Error::DuplicatedEffector(effector.id));
+            }
+            if !neurons.contains(&effector.source) {
+                return Err(Error::EffectorSourceDoesNotExists(
+                    effector.id,
+                    effector.source,
+                ));
+            }
+            if !effector_sources.insert(effector.source) {
+                return Err(Error::NeuronHasMultipleEffectors(effector.source));
+            }
+            if sensor_targets.contains(&effector.source) {
+                return Err(Error::NeuronIsBothSensorAndEffector(effector.source));
+            }
+            if !effector.potential.is_finite() {
+                return Err(Error::EffectorHasInvalidPotential(effector.id));
+            }
+        }
+        Ok(())
+    }
+
     pub fn process(&mut self, delta_time: Scalar) -> Result<()> {
+        self.time += delta_time;
         if self.neurons.is_empty() {
             return Ok(());
         }
 
+        let models = self.config.neuron_models();
+        let plasticity = self.config.plasticity_rules();
         let Config {
             propagation_speed,
-            action_potential_treshold,
             synapse_inactivity_time,
-            neuron_potential_decay,
             synapse_overdose_receptors,
-            receptors_excitation,
             receptors_inhibition,
             synapse_propagation_decay,
             synapse_new_connection_receptors,
+            eligibility_trace_time,
+            firing_rate_time,
             ..
         } = self.config;
 
+        // sensors input phase.
+        {
+            let mut rng = self.rng.clone();
+            let inputs = self
+                .sensors
+                .iter_mut()
+                .filter_map(|sensor| {
+                    sensor
+                        .process(delta_time, &mut rng)
+                        .map(|potential| (sensor.target, potential))
+                })
+                .collect::<Vec<_>>();
+            self.rng = rng;
+            for (id, potential) in inputs {
+                if let Some(neuron) = self.neuron_mut(id) {
+                    neuron.receive(models.get(neuron.model()), potential);
+                }
+            }
+        }
+
         // potential summation phase.
         {
-            let dtpd = delta_time * neuron_potential_decay;
+            let (rate_decay, rate_spike) = if firing_rate_time > 0.0 {
+                (
+                    (-delta_time / firing_rate_time).exp(),
+                    1.0 / firing_rate_time,
+                )
+            } else {
+                (0.0, 0.0)
+            };
             let neurons_triggering = iter_mut!(self.neurons)
                 .filter_map(|neuron| {
-                    let potential = neuron.potential();
-                    let status = if potential >= action_potential_treshold {
-                        neuron.fire();
-                        true
-                    } else {
-                        false
-                    };
-                    neuron.process_potential(dtpd);
-                    if status {
-                        Some((neuron.id(), potential))
-                    } else {
-                        None
-                    }
+                    let model = models.get(neuron.model());
+                    let fired = neuron.process(model, delta_time);
+                    let spike = if fired.is_some() { rate_spike } else { 0.0 };
+                    neuron.update_firing_rate(rate_decay, spike);
+                    fired.map(|potential| (neuron.id(), potential))
                 })
                 .collect::<Vec<_>>();
+            let fired = neurons_triggering
+                .iter()
+                .map(|(id, _)| *id)
+                .collect::<Vec<_>>();
+            self.apply_spike_timing_plasticity(plasticity.get(), &fired);
+            if let Some(events) = &mut self.events {
+                events.extend(fired.iter().map(|id| BrainEvent::NeuronFired(*id)));
+            }
             for (id, p) in neurons_triggering {
-                let count = iter!(self.synapses)
-                    .filter(|s| s.inactivity <= 0.0 && s.source == id)
-                    .count();
-                if count > 0 {
-                    let p = p / count as Scalar;
-                    iter_mut!(self.synapses)
-                        .filter(|s| s.inactivity <= 0.0 && s.source == id)
-                        .for_each(|s| {
-                            let under = if let Some(o) = synapse_overdose_receptors {
-                                s.receptors < o
-                            } else {
-                                true
-                            };
-                            if under {
-                                s.impulses.push(Impulse {
-                                    potential: p,
-                                    timeout: s.distance,
-                                });
-                            }
-                            s.inactivity = synapse_inactivity_time;
-                        });
+                let synapses = &self.synapses;
+                let indices = self
+                    .index
+                    .outgoing(id)
+                    .iter()
+                    .filter_map(|to| self.index.synapse(id, *to))
+                    .filter(|index| synapses[*index].inactivity <= 0.0)
+                    .collect::<Vec<_>>();
+                if !indices.is_empty() {
+                    let p = p / indices.len() as Scalar;
+                    for index in indices {
+                        let s = &mut self.synapses[index];
+                        let under = if let Some(o) = synapse_overdose_receptors {
+                            s.receptors < o
+                        } else {
+                            true
+                        };
+                        if under {
+                            s.impulses.push(Impulse {
+                                potential: p,
+                                timeout: s.distance,
+                            });
+                        }
+                        s.inactivity = synapse_inactivity_time;
+                    }
                 }
             }
         }
@@ -708,7 +1343,12 @@ impl Brain {
         // impulse propagation phase.
         {
             let s = propagation_speed * delta_time;
-            let r = receptors_excitation * delta_time;
+            let r = plasticity.get().impulse_arrived(delta_time);
+            let e = if eligibility_trace_time > 0.0 {
+                (-delta_time / eligibility_trace_time).exp()
+            } else {
+                0.0
+            };
             let d = synapse_propagation_decay * s;
             let neurons_to_trigger = iter_mut!(self.synapses)
                 .flat_map(|synapse| {
@@ -721,6 +1361,7 @@ impl Brain {
                         }
                     }
                     synapse.receptors += estimated_count as Scalar * r;
+                    synapse.eligibility = synapse.eligibility * e + estimated_count as Scalar;
                     let mut neurons_to_trigger = Vec::with_capacity(estimated_count);
                     if estimated_count > 0 {
                         synapse.impulses = synapse
@@ -732,7 +1373,16 @@ impl Brain {
                                 } else if impulse.timeout > 0.0 {
                                     Some(*impulse)
                                 } else {
-                                    neurons_to_trigger.push((synapse.target, impulse.potential));
+                                    let potential = if synapse.inhibitory {
+                                        -impulse.potential
+                                    } else {
+                                        impulse.potential
+                                    };
+                                    neurons_to_trigger.push((
+                                        synapse.source,
+                                        synapse.target,
+                                        potential,
+                                    ));
                                     None
                                 }
                             })
@@ -742,14 +1392,12 @@ impl Brain {
                     neurons_to_trigger
                 })
                 .collect::<Vec<_>>();
-            iter_mut!(self.neurons).for_each(|neuron| {
-                let nid = neuron.id();
-                for (id, potential) in &neurons_to_trigger {
-                    if nid == *id {
-                        neuron.push_potential(*potential);
-                    }
+            for (from, id, potential) in neurons_to_trigger {
+                if let Some(neuron) = self.neuron_mut(id) {
+                    neuron.receive(models.get(neuron.model()), potential);
                 }
-            });
+                self.emit(BrainEvent::ImpulseDelivered(from, id, potential));
+            }
         }
 
         // inhibition and reconnection phase.
@@ -766,16 +1414,13 @@ impl Brain {
                     }
                 })
                 .collect::<Vec<_>>();
-            let neurons_to_reconnect = iter!(synapses_to_remove)
+            let mut rng = self.rng.clone();
+            let neurons_to_reconnect = synapses_to_remove
+                .iter()
                 .filter_map(|index| {
                     let s = &self.synapses[*index];
                     if s.receptors <= 0.0 {
-                        #[cfg(feature = "parallel")]
-                        let neuron = self.neurons.par_iter().find_any(|n| n.id() == s.source);
-                        #[cfg(not(feature = "parallel"))]
-                        let neuron = self.neurons.iter().find(|n| n.id() == s.source);
-                        if let Some(neuron) = neuron {
-                            let mut rng = thread_rng();
+                        if let Some(neuron) = self.neuron(s.source) {
                             if let Some(id) = self.select_neuron(neuron.position(), &mut rng) {
                                 if s.source != id
                                     && !self.are_neurons_connected(s.source, id)
@@ -789,8 +1434,11 @@ impl Brain {
                     None
                 })
                 .collect::<Vec<_>>();
+            self.rng = rng;
             for index in synapses_to_remove.into_iter().rev() {
-                self.synapses.swap_remove(index);
+                let synapse = self.index.swap_remove_synapse(&mut self.synapses, index);
+                self.track(|c| c.synapse_removed(synapse.source, synapse.target));
+                self.emit(BrainEvent::SynapseInhibited(synapse.source, synapse.target));
             }
             for (from, to) in neurons_to_reconnect {
                 self.bind_neurons(from, to)?;
@@ -802,8 +1450,7 @@ impl Brain {
             let neurons_to_remove = iter!(self.neurons)
                 .enumerate()
                 .filter_map(|(i, n)| {
-                    let id = n.id();
-                    if !iter!(self.synapses).any(|s| s.source == id || s.target == id) {
+                    if !self.index.has_connections(n.id()) {
                         Some(i)
                     } else {
                         None
@@ -811,61 +1458,34 @@ impl Brain {
                 })
                 .collect::<Vec<_>>();
             for index in neurons_to_remove.into_iter().rev() {
-                let id = self.neurons.swap_remove(index).id();
-                #[cfg(feature = "parallel")]
-                let index = self
-                    .synapses
-                    .par_iter()
-                    .position_any(|s| s.source == id || s.target == id);
-                #[cfg(not(feature = "parallel"))]
-                let index = self
-                    .synapses
-                    .iter()
-                    .position(|s| s.source == id || s.target == id);
-                while let Some(index) = index {
-                    self.synapses.swap_remove(index);
-                }
-                #[cfg(feature = "parallel")]
-                let index = self.sensors.par_iter().position_any(|s| s.target == id);
-                #[cfg(not(feature = "parallel"))]
-                let index = self.sensors.iter().position(|s| s.target == id);
-                while let Some(index) = index {
-                    self.sensors.swap_remove(index);
-                }
-                #[cfg(feature = "parallel")]
-                let index = self.effectors.par_iter().position_any(|e| e.source == id);
-                #[cfg(not(feature = "parallel"))]
-                let index = self.effectors.iter().position(|e| e.source == id);
-                while let Some(index) = index {
-                    self.effectors.swap_remove(index);
-                }
+                self.remove_neuron_at(index);
             }
         }
 
         // accumulating effector potentials phase.
         {
-            for effector in &mut self.effectors {
-                #[cfg(feature = "parallel")]
-                let neuron = self
-                    .neurons
-                    .par_iter()
-                    .find_any(|n| n.id() == effector.source);
-                #[cfg(not(feature = "parallel"))]
-                let neuron = self.neurons.iter().find(|n| n.id() == effector.source);
-                if let Some(neuron) = neuron {
-                    effector.potential = neuron.potential();
+            let neurons = &self.neurons;
+            let index = &self.index;
+            let time = self.time;
+            iter_mut!(self.effectors).for_each(|effector| {
+                if let Some(i) = index.neuron(effector.source) {
+                    let neuron = &neurons[i];
+                    let fired = neuron.last_spike() == Some(time);
+                    effector.process(neuron.potential(), fired, time, delta_time);
                 }
-            }
+            });
         }
 
         // creating new connections phase.
         if let Some(r) = synapse_new_connection_receptors {
-            let synapses_to_connect = iter!(self.synapses)
+            let mut rng = self.rng.clone();
+            let synapses_to_connect = self
+                .synapses
+                .iter()
                 .enumerate()
                 .filter_map(|(i, s)| {
                     if s.receptors > r {
                         if let Some(neuron) = self.neuron(s.source) {
-                            let mut rng = thread_rng();
                             if let Some(id) = self.select_neuron(neuron.position(), &mut rng) {
                                 if s.source != id
                                     && !self.are_neurons_connected(s.source, id)
@@ -879,6 +1499,7 @@ impl Brain {
                     None
                 })
                 .collect::<Vec<_>>();
+            self.rng = rng;
             for (index, from, to) in synapses_to_connect.into_iter().rev() {
                 if let Some(receptors) = self.bind_neurons(from, to)? {
                     self.synapses[index].receptors -= receptors;
@@ -889,6 +1510,90 @@ impl Brain {
         Ok(())
     }
 
+    /// Strengthens (positive reward) or weakens (negative reward) recently active synapses,
+    /// proportionally to their eligibility traces.
+    pub fn apply_reward(&mut self, value: Scalar) {
+        let rate = value * self.config.reward_learning_rate;
+        iter_mut!(self.synapses).for_each(|synapse| {
+            synapse.receptors += rate * synapse.eligibility;
+        });
+    }
+
+    /// Applies reward only to synapses whose target neuron lies within given region.
+    pub fn apply_regional_reward(&mut self, value: Scalar, center: Position, radius: Scalar) {
+        let rate = value * self.config.reward_learning_rate;
+        let neurons = &self.neurons;
+        let index = &self.index;
+        iter_mut!(self.synapses).for_each(|synapse| {
+            if let Some(i) = index.neuron(synapse.target) {
+                if neurons[i].position().distance(center) <= radius {
+                    synapse.receptors += rate * synapse.eligibility;
+                }
+            }
+        });
+    }
+
+    // updates receptors of synapses around fired neurons and marks their spike time.
+    fn apply_spike_timing_plasticity(&mut self, rule: &dyn PlasticityRule, fired: &[NeuronID]) {
+        let time = self.time;
+        let since = |id| {
+            self.neuron(id)
+                .and_then(|n| n.last_spike())
+                .map(|t| time - t)
+        };
+        let changes = fired
+            .iter()
+            .flat_map(|id| {
+                let id = *id;
+                let pre = self
+                    .index
+                    .outgoing(id)
+                    .iter()
+                    .map(move |to| ((id, *to), rule.pre_spike(since(*to))));
+                let post = self
+                    .index
+                    .incoming(id)
+                    .iter()
+                    .map(move |from| ((*from, id), rule.post_spike(since(*from))));
+                pre.chain(post).collect::<Vec<_>>()
+            })
+            .filter(|(_, change)| *change != 0.0)
+            .collect::<Vec<_>>();
+        for ((from, to), change) in changes {
+            if let Some(index) = self.index.synapse(from, to) {
+                self.synapses[index].receptors += change;
+            }
+        }
+        for id in fired {
+            if let Some(neuron) = self.neuron_mut(*id) {
+                neuron.set_last_spike(Some(time));
+            }
+        }
+    }
+
+    #[cfg(test)]
+    pub(crate) fn has_dangling_references(&self) -> bool {
+        let exists = |id| self.neurons.iter().any(|n| n.id() == id);
+        self.neurons.iter().any(|n| n.owner_id() != self.id)
+            || self
+                .synapses
+                .iter()
+                .any(|s| !exists(s.source) || !exists(s.target))
+            || self.sensors.iter().any(|s| !exists(s.target))
+            || self.effectors.iter().any(|e| !exists(e.source))
+    }
+
+    #[cfg(test)]
+    pub(crate) fn is_index_valid(&self) -> bool {
+        BrainIndex::build(
+            &self.neurons,
+            &self.synapses,
+            &self.sensors,
+            &self.effectors,
+        )
+        .matches(&self.index)
+    }
+
     fn select_neuron<R>(&self, position: Position, rng: &mut R) -> Option<NeuronID>
     where
         R: Rng,
@@ -896,7 +1601,7 @@ impl Brain {
         let srr = self.config.synapse_reconnection_range;
         let filtered = iter!(self.neurons)
             .filter_map(|neuron| {
-                if iter!(self.sensors).any(|s| s.target == neuron.id()) {
+                if self.index.neuron_sensor(neuron.id()).is_some() {
                     return None;
                 }
                 if let Some(srr) = srr {
@@ -920,38 +1625,67 @@ impl Brain {
     }
 
     pub fn build_activity_map(&self, flags: usize) -> BrainActivityMap {
-        let connections = if flags & activity::CONNECTIONS != 0 {
-            iter!(self.synapses)
-                .map(|s| {
-                    let from = self.neuron(s.source).unwrap().position();
-                    let to = self.neuron(s.target).unwrap().position();
-                    (from, to, s.receptors)
-                })
-                .collect()
-        } else {
-            vec![]
+        let connections = |inhibitory| {
+            if flags & activity::CONNECTIONS != 0 {
+                iter!(self.synapses)
+                    .filter(|s| s.inhibitory == inhibitory)
+                    .map(|s| {
+                        let from = self.neuron(s.source).unwrap().position();
+                        let to = self.neuron(s.target).unwrap().position();
+                        (from, to, s.receptors)
+                    })
+                    .collect()
+            } else {
+                vec![]
+            }
         };
-        let impulses = if flags & activity::IMPULSES != 0 {
-            iter!(self.synapses)
-                .map(|s| {
-                    let from = self.neuron(s.source).unwrap().position();
-                    let to = self.neuron(s.target).unwrap().position();
-                    let distance = from.distance(to);
-                    iter!(s.impulses)
-                        .map(|i| {
-                            let factor = if distance > 0.0 {
-                                1.0 - i.timeout.max(0.0).min(distance) / distance
-                            } else {
-                                0.0
-                            };
-                            (from, to, factor)
-                        })
-                        .collect::<Vec<_>>()
-                })
-                .flatten()
-                .collect()
-        } else {
-            vec![]
+        let impulses = |inhibitory| {
+            if flags & activity::IMPULSES != 0 {
+                iter!(self.synapses)
+                    .filter(|s| s.inhibitory == inhibitory)
+                    .map(|s| {
+                        let from = self.neuron(s.source).unwrap().position();
+                        let to = self.neuron(s.target).unwrap().position();
+                        let distance = from.distance(to);
+                        iter!(s.impulses)
+                            .map(|i| (from, to, impulse_factor(i, distance)))
+                            .collect::<Vec<_>>()
+                    })
+                    .flatten()
+                    .collect()
+            } else {
+                vec![]
+            }
+        };
+        let impulses_potential = |inhibitory| {
+            if flags & activity::IMPULSES != 0 {
+                iter!(self.synapses)
+                    .filter(|s| s.inhibitory == inhibitory)
+                    .flat_map(|s| iter!(s.impulses).map(|i| i.potential).collect::<Vec<_>>())
+                    .collect()
+            } else {
+                vec![]
+            }
+        };
+        let neurons = |inhibitory| {
+            if flags & activity::NEURONS != 0 {
+                iter!(self.neurons)
+                    .filter(|n| n.is_inhibitory() == inhibitory)
+                    .map(|n| n.position())
+                    .collect()
+            } else {
+                vec![]
+            }
+        };
+        let neurons_potential = |inhibitory| {
+            if flags & activity::NEURONS != 0 {
+                iter!(self.neurons)
+                    .filter(|n| n.is_inhibitory() == inhibitory)
+                    .map(|n| n.potential())
+                    .collect()
+            } else {
+                vec![]
+            }
         };
         let sensors = if flags & activity::SENSORS != 0 {
             iter!(self.sensors)
@@ -967,21 +1701,80 @@ impl Brain {
         } else {
             vec![]
         };
-        let neurons = if flags & activity::NEURONS != 0 {
-            iter!(self.neurons).map(|n| n.position()).collect()
-        } else {
-            vec![]
-        };
 
         BrainActivityMap {
-            connections,
-            impulses,
+            connections: connections(false),
+            impulses: impulses(false),
             sensors,
             effectors,
-            neurons,
+            neurons: neurons(false),
+            inhibitory_connections: connections(true),
+            inhibitory_impulses: impulses(true),
+            inhibitory_neurons: neurons(true),
+            neurons_potential: neurons_potential(false),
+            inhibitory_neurons_potential: neurons_potential(true),
+            impulses_potential: impulses_potential(false),
+            inhibitory_impulses_potential: impulses_potential(true),
         }
     }
 
+    /// Builds static structure of brain and starts tracking its changes, which get reported by
+    /// following `build_activity_delta` calls.
+    pub fn build_topology(&mut self) -> BrainTopology {
+        self.changes = Some(Default::default());
+        BrainTopology {
+            neurons: self
+                .neurons
+                .iter()
+                .map(|n| (n.id(), n.position(), n.is_inhibitory()))
+                .collect(),
+            synapses: self
+                .synapses
+                .iter()
+                .map(|s| (s.source, s.target, s.inhibitory))
+                .collect(),
+            sensors: self.sensors.iter().map(|s| (s.id, s.target)).collect(),
+            effectors: self.effectors.iter().map(|e| (e.id, e.source)).collect(),
+        }
+    }
+
+    /// Takes changes made to brain since last delta (or since `build_topology`) together with
+    /// current activity selected by flags. Call it after every `process` and apply it to topology.
+    pub fn build_activity_delta(&mut self, flags: usize) -> BrainActivityDelta {
+        let mut delta = self.changes.replace(Default::default()).unwrap_or_default();
+        if flags & activity::NEURONS != 0 {
+            delta.fired_neurons = self
+                .neurons
+                .iter()
+                .filter(|n| n.last_spike() == Some(self.time))
+                .map(|n| n.id())
+                .collect();
+            delta.neurons_potential = self
+                .neurons
+                .iter()
+                .map(|n| (n.id(), n.potential()))
+                .collect();
+        }
+        if flags & activity::CONNECTIONS != 0 {
+            delta.synapses_receptors = self
+                .synapses
+                .iter()
+                .map(|s| (s.source, s.target, s.receptors))
+                .collect();
+        }
+        if flags & activity::IMPULSES != 0 {
+            for s in &self.synapses {
+                for i in &s.impulses {
+                    delta
+                        .impulses
+                        .push((s.source, s.target, impulse_factor(i, s.distance)));
+                    delta.impulses_potential.push(i.potential);
+                }
+            }
+        }
+        delta
+    }
+
     pub fn build_activity_stats(&self) -> BrainActivityStats {
         let neurons_potential = self.get_neurons_potential();
         let neurons_potential_min = iter!(self.neurons)
@@ -1042,6 +1835,57 @@ impl Brain {
             .map(|s| s.receptors)
             .max_by(|a, b| a.partial_cmp(&b).unwrap())
             .unwrap_or(0.0);
+        let mut synapses_receptors_histogram = [0; STATS_HISTOGRAM_BINS];
+        let receptors_range = synapses_receptors_max - synapses_receptors_min;
+        for synapse in &self.synapses {
+            let bin = if receptors_range > 0.0 {
+                ((synapse.receptors - synapses_receptors_min) / receptors_range
+                    * STATS_HISTOGRAM_BINS as Scalar) as usize
+            } else {
+                0
+            };
+            synapses_receptors_histogram[bin.min(STATS_HISTOGRAM_BINS - 1)] += 1;
+        }
+        let mut incoming_neuron_connections_histogram = [0; STATS_HISTOGRAM_BINS];
+        let mut outgoing_neuron_connections_histogram = [0; STATS_HISTOGRAM_BINS];
+        for (incoming, outgoing) in &neuron_connections {
+            incoming_neuron_connections_histogram[(*incoming).min(STATS_HISTOGRAM_BINS - 1)] += 1;
+            outgoing_neuron_connections_histogram[(*outgoing).min(STATS_HISTOGRAM_BINS - 1)] += 1;
+        }
+        let (mean_firing_rate, active_neurons) = if self.neurons.is_empty() {
+            (0.0, 0.0)
+        } else {
+            let count = self.neurons.len() as Scalar;
+            let rate = iter!(self.neurons).map(|n| n.firing_rate()).sum::<Scalar>();
+            let active = iter!(self.neurons)
+                .filter(|n| match n.last_spike() {
+                    Some(t) => self.time - t <= self.config.firing_rate_time,
+                    None => false,
+                })
+                .count();
+            (rate / count, active as Scalar / count)
+        };
+        let sources = self.sensors.iter().map(|s| s.target).collect::<Vec<_>>();
+        let reachable = self.index.distances_from(&sources);
+        let reachable_effectors = self
+            .effectors
+            .iter()
+            .filter(|e| reachable.contains_key(&e.source))
+            .count();
+        let (paths_length, paths_count) = sources.iter().fold((0, 0), |accum, source| {
+            let distances = self.index.distances_from(&[*source]);
+            self.effectors
+                .iter()
+                .filter_map(|e| distances.get(&e.source))
+                .fold(accum, |(length, count), distance| {
+                    (length + distance, count + 1)
+                })
+        });
+        let sensor_effector_path_length = if paths_count > 0 {
+            paths_length as Scalar / paths_count as Scalar
+        } else {
+            0.0
+        };
 
         BrainActivityStats {
             neurons_count: self.neurons.len(),
@@ -1063,11 +1907,21 @@ impl Brain {
             incoming_neuron_connections: neuron_connections_min.0..neuron_connections_max.0,
             outgoing_neuron_connections: neuron_connections_min.1..neuron_connections_max.1,
             synapses_receptors: synapses_receptors_min..synapses_receptors_max,
+            mean_firing_rate,
+            active_neurons,
+            synapses_receptors_histogram,
+            incoming_neuron_connections_histogram,
+            outgoing_neuron_connections_histogram,
+            strongly_connected_components: self
+                .index
+                .strongly_connected_components_count(&self.neurons),
+            sensor_effector_path_length,
+            reachable_effectors,
         }
     }
 
     pub fn ignite_random_synapses(&mut self, count: usize, potential: Range<Scalar>) {
-        let mut rng = thread_rng();
+        let rng = &mut self.rng;
         for _ in 0..count {
             let index = rng.gen_range(0, self.synapses.len()) % self.synapses.len();
             let synapse = &mut self.synapses[index];
@@ -1082,3 +1936,52 @@ impl Brain {
         }
     }
 }
+
+fn impulse_factor(impulse: &Impulse, distance: Scalar) -> Scalar {
+    if distance > 0.0 {
+        1.0 - impulse.timeout.max(0.0).min(distance) / distance
+    } else {
+        0.0
+    }
+}
+
+fn io_order<T, K, F>(items: &[T], key: F) -> Vec<ID<K>>
+where
+    K: Clone,
+    F: Fn(&T) -> (ID<K>, &Label),
+{
+    let mut result = items.iter().map(key).collect::<Vec<_>>();
+    result.sort_by(|(a, la), (b, lb)| match (&la.name, &lb.name) {
+        (Some(na), Some(nb)) => na.cmp(nb).then(a.cmp(b)),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => a.cmp(b),
+    });
+    result.into_iter().map(|(id, _)| id).collect()
+}
+
+fn merge_unique<T, K, F, R>(a: &[T], b: &[T], key: F, rng: &mut R) -> Vec<T>
+where
+    T: Clone,
+    K: Eq + Hash,
+    F: Fn(&T) -> K,
+    R: Rng,
+{
+    let mut result = a.to_vec();
+    let mut indices = result
+        .iter()
+        .enumerate()
+        .map(|(i, item)| (key(item), i))
+        .collect::<HashMap<_, _>>();
+    for item in b {
+        if let Some(index) = indices.get(&key(item)) {
+            if rng.gen() {
+                result[*index] = item.clone();
+            }
+        } else {
+            indices.insert(key(item), result.len());
+            result.push(item.clone());
+        }
+    }
+    result
+}
@@ -1,6 +1,8 @@
 use crate::brain::Brain;
 use crate::config::Config;
+use crate::graph::connect_unreachable_effectors;
 use crate::neuron::{NeuronID, Position};
+use crate::rng::BrainRng;
 use crate::Scalar;
 use rand::{thread_rng, Rng};
 use serde::{Deserialize, Serialize};
@@ -18,6 +20,12 @@ pub struct BrainBuilder {
     effectors: usize,
     no_loop_connections: bool,
     max_connecting_tries: usize,
+    #[serde(default)]
+    inhibitory_neurons: Scalar,
+    #[serde(default)]
+    reachable_effectors: bool,
+    #[serde(default)]
+    seed: Option<u64>,
 }
 
 impl Default for BrainBuilder {
@@ -33,6 +41,9 @@ impl Default for BrainBuilder {
             effectors: 1,
             no_loop_connections: true,
             max_connecting_tries: 10,
+            inhibitory_neurons: 0.0,
+            reachable_effectors: false,
+            seed: None,
         }
     }
 }
@@ -92,10 +103,39 @@ impl BrainBuilder {
         self
     }
 
-    pub fn build(mut self) -> Brain {
-        let mut brain = Brain::new();
+    /// Sets probability (0..1) of new neuron being inhibitory.
+    pub fn inhibitory_neurons(mut self, value: Scalar) -> Self {
+        self.inhibitory_neurons = value;
+        self
+    }
+
+    /// Connects effectors that no sensor can reach with neurons that receive sensor input.
+    /// It is best effort: effectors stay unreachable when no such neuron can be bound to them
+    /// (e.g. when brain has no sensors).
+    pub fn reachable_effectors(mut self, value: bool) -> Self {
+        self.reachable_effectors = value;
+        self
+    }
+
+    /// Makes building deterministic by seeding random number generator with given value.
+    pub fn seed(mut self, value: u64) -> Self {
+        self.seed = Some(value);
+        self
+    }
+
+    pub fn build(self) -> Brain {
+        match self.seed {
+            Some(seed) => self.build_with_rng(&mut BrainRng::from_u64(seed)),
+            None => self.build_with_rng(&mut thread_rng()),
+        }
+    }
+
+    pub fn build_with_rng<R>(mut self, rng: &mut R) -> Brain
+    where
+        R: Rng,
+    {
+        let mut brain = Brain::with_rng(BrainRng::from_rng_source(rng));
         brain.set_config(self.config.clone());
-        let mut rng = thread_rng();
 
         let mut neurons = vec![];
         neurons.push(brain.create_neuron(Position {
@@ -104,7 +144,7 @@ impl BrainBuilder {
             z: 0.0,
         }));
         for _ in 0..self.neurons {
-            neurons.push(self.make_neighbor_neuron(&neurons, &mut brain, &mut rng));
+            neurons.push(self.make_neighbor_neuron(&neurons, &mut brain, rng));
         }
 
         let neuron_positions = neurons
@@ -113,27 +153,25 @@ impl BrainBuilder {
             .collect::<Vec<_>>();
         for _ in 0..self.sensors {
             let mut tries = self.max_connecting_tries + 1;
-            while tries > 0 && !self.make_peripheral_sensor(&neuron_positions, &mut brain, &mut rng)
-            {
+            while tries > 0 && !self.make_peripheral_sensor(&neuron_positions, &mut brain, rng) {
                 tries -= 1;
             }
         }
         for _ in 0..self.effectors {
             let mut tries = self.max_connecting_tries + 1;
-            while tries > 0
-                && !self.make_peripheral_effector(&neuron_positions, &mut brain, &mut rng)
-            {
+            while tries > 0 && !self.make_peripheral_effector(&neuron_positions, &mut brain, rng) {
                 tries -= 1;
             }
         }
         for _ in 0..self.connections {
             let mut tries = self.max_connecting_tries + 1;
-            while tries > 0
-                && !self.connect_neighbor_neurons(&neuron_positions, &mut brain, &mut rng)
-            {
+            while tries > 0 && !self.connect_neighbor_neurons(&neuron_positions, &mut brain, rng) {
                 tries -= 1;
             }
         }
+        if self.reachable_effectors {
+            connect_unreachable_effectors(&mut brain);
+        }
         for id in brain.get_neurons() {
             if !brain.does_neuron_has_connections(id) {
                 drop(brain.kill_neuron(id));
@@ -196,7 +234,11 @@ impl BrainBuilder {
         let origin = neurons[rng.gen_range(0, neurons.len()) % neurons.len()];
         let origin_pos = brain.neuron(origin).unwrap().position();
         let new_position = self.make_new_position(origin_pos, distance, rng);
-        brain.create_neuron(new_position)
+        let id = brain.create_neuron(new_position);
+        if self.inhibitory_neurons > 0.0 && rng.gen::<Scalar>() < self.inhibitory_neurons {
+            drop(brain.set_neuron_inhibitory(id, true));
+        }
+        id
     }
 
     fn connect_neighbor_neurons<R>(
@@ -0,0 +1,296 @@
+use crate::effector::{Effector, EffectorID};
+use crate::neuron::{Neuron, NeuronID, Synapse};
+use crate::sensor::{Sensor, SensorID};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt;
+
+/// Lookup tables derived from brain storage.
+/// Maps identifiers to dense storage indices and keeps synapses adjacency of every neuron,
+/// so that all operations on IDs are O(1) instead of linear scans.
+/// Index is never serialized - it is rebuilt from storage after deserialization.
+#[derive(Clone, Default)]
+pub(crate) struct BrainIndex {
+    neurons: HashMap<NeuronID, usize>,
+    synapses: HashMap<(NeuronID, NeuronID), usize>,
+    incoming: HashMap<NeuronID, Vec<NeuronID>>,
+    outgoing: HashMap<NeuronID, Vec<NeuronID>>,
+    sensors: HashMap<SensorID, usize>,
+    effectors: HashMap<EffectorID, usize>,
+    sensor_targets: HashMap<NeuronID, SensorID>,
+    effector_sources: HashMap<NeuronID, EffectorID>,
+}
+
+impl BrainIndex {
+    pub fn build(
+        neurons: &[Neuron],
+        synapses: &[Synapse],
+        sensors: &[Sensor],
+        effectors: &[Effector],
+    ) -> Self {
+        let mut result = Self::default();
+        for (i, n) in neurons.iter().enumerate() {
+            result.neurons.insert(n.id(), i);
+        }
+        for (i, s) in synapses.iter().enumerate() {
+            result.insert_synapse(s, i);
+        }
+        for (i, s) in sensors.iter().enumerate() {
+            result.sensors.insert(s.id, i);
+            result.sensor_targets.insert(s.target, s.id);
+        }
+        for (i, e) in effectors.iter().enumerate() {
+            result.effectors.insert(e.id, i);
+            result.effector_sources.insert(e.source, e.id);
+        }
+        result
+    }
+
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    #[inline]
+    pub fn neuron(&self, id: NeuronID) -> Option<usize> {
+        self.neurons.get(&id).cloned()
+    }
+
+    #[inline]
+    pub fn synapse(&self, from: NeuronID, to: NeuronID) -> Option<usize> {
+        self.synapses.get(&(from, to)).cloned()
+    }
+
+    #[inline]
+    pub fn incoming(&self, id: NeuronID) -> &[NeuronID] {
+        self.incoming.get(&id).map_or(&[], |v| v.as_slice())
+    }
+
+    #[inline]
+    pub fn outgoing(&self, id: NeuronID) -> &[NeuronID] {
+        self.outgoing.get(&id).map_or(&[], |v| v.as_slice())
+    }
+
+    #[inline]
+    pub fn has_connections(&self, id: NeuronID) -> bool {
+        self.incoming.contains_key(&id) || self.outgoing.contains_key(&id)
+    }
+
+    #[inline]
+    pub fn sensor(&self, id: SensorID) -> Option<usize> {
+        self.sensors.get(&id).cloned()
+    }
+
+    #[inline]
+    pub fn effector(&self, id: EffectorID) -> Option<usize> {
+        self.effectors.get(&id).cloned()
+    }
+
+    #[inline]
+    pub fn neuron_sensor(&self, id: NeuronID) -> Option<SensorID> {
+        self.sensor_targets.get(&id).cloned()
+    }
+
+    #[inline]
+    pub fn neuron_effector(&self, id: NeuronID) -> Option<EffectorID> {
+        self.effector_sources.get(&id).cloned()
+    }
+
+    /// Shortest distances (in synapses) from given neurons to every neuron reachable from them.
+    pub fn distances_from(&self, sources: &[NeuronID]) -> HashMap<NeuronID, usize> {
+        let mut result = sources.iter().map(|id| (*id, 0)).collect::<HashMap<_, _>>();
+        let mut queue = sources.iter().cloned().collect::<VecDeque<_>>();
+        while let Some(id) = queue.pop_front() {
+            let distance = result[&id] + 1;
+            for other in self.outgoing(id) {
+                if !result.contains_key(other) {
+                    result.insert(*other, distance);
+                    queue.push_back(*other);
+                }
+            }
+        }
+        result
+    }
+
+    /// Counts strongly connected components of synapses graph (Kosaraju algorithm).
+    pub fn strongly_connected_components_count(&self, neurons: &[Neuron]) -> usize {
+        let mut visited = HashSet::with_capacity(neurons.len());
+        let mut order = Vec::with_capacity(neurons.len());
+        for neuron in neurons {
+            let id = neuron.id();
+            if !visited.insert(id) {
+                continue;
+            }
+            let mut stack = vec![(id, 0)];
+            while let Some((id, next)) = stack.pop() {
+                let outgoing = self.outgoing(id);
+                if next < outgoing.len() {
+                    stack.push((id, next + 1));
+                    let other = outgoing[next];
+                    if visited.insert(other) {
+                        stack.push((other, 0));
+                    }
+                } else {
+                    order.push(id);
+                }
+            }
+        }
+        let mut assigned = HashSet::with_capacity(neurons.len());
+        let mut count = 0;
+        for id in order.into_iter().rev() {
+            if !assigned.insert(id) {
+                continue;
+            }
+            count += 1;
+            let mut stack = vec![id];
+            while let Some(id) = stack.pop() {
+                for other in self.incoming(id) {
+                    if assigned.insert(*other) {
+                        stack.push(*other);
+                    }
+                }
+            }
+        }
+        count
+    }
+
+    pub fn push_neuron(&mut self, neurons: &mut Vec<Neuron>, neuron: Neuron) {
+        self.neurons.insert(neuron.id(), neurons.len());
+        neurons.push(neuron);
+    }
+
+    pub fn swap_remove_neuron(&mut self, neurons: &mut Vec<Neuron>, index: usize) -> Neuron {
+        let neuron = neurons.swap_remove(index);
+        self.neurons.remove(&neuron.id());
+        if let Some(moved) = neurons.get(index) {
+            self.neurons.insert(moved.id(), index);
+        }
+        neuron
+    }
+
+    pub fn push_synapse(&mut self, synapses: &mut Vec<Synapse>, synapse: Synapse) {
+        self.insert_synapse(&synapse, synapses.len());
+        synapses.push(synapse);
+    }
+
+    pub fn swap_remove_synapse(&mut self, synapses: &mut Vec<Synapse>, index: usize) -> Synapse {
+        let synapse = synapses.swap_remove(index);
+        self.synapses.remove(&(synapse.source, synapse.target));
+        remove_adjacent(&mut self.outgoing, synapse.source, synapse.target);
+        remove_adjacent(&mut self.incoming, synapse.target, synapse.source);
+        if let Some(moved) = synapses.get(index) {
+            self.synapses.insert((moved.source, moved.target), index);
+        }
+        synapse
+    }
+
+    pub fn push_sensor(&mut self, sensors: &mut Vec<Sensor>, sensor: Sensor) {
+        self.sensors.insert(sensor.id, sensors.len());
+        self.sensor_targets.insert(sensor.target, sensor.id);
+        sensors.push(sensor);
+    }
+
+    pub fn swap_remove_sensor(&mut self, sensors: &mut Vec<Sensor>, index: usize) -> Sensor {
+        let sensor = sensors.swap_remove(index);
+        self.sensors.remove(&sensor.id);
+        if self.sensor_targets.get(&sensor.target) == Some(&sensor.id) {
+            self.sensor_targets.remove(&sensor.target);
+        }
+        if let Some(moved) = sensors.get(index) {
+            self.sensors.insert(moved.id, index);
+        }
+        sensor
+    }
+
+    pub fn push_effector(&mut self, effectors: &mut Vec<Effector>, effector: Effector) {
+        self.effectors.insert(effector.id, effectors.len());
+        self.effector_sources.insert(effector.source, effector.id);
+        effectors.push(effector);
+    }
+
+    pub fn swap_remove_effector(
+        &mut self,
+        effectors: &mut Vec<Effector>,
+        index: usize,
+    ) -> Effector {
+        let effector = effectors.swap_remove(index);
+        self.effectors.remove(&effector.id);
+        if self.effector_sources.get(&effector.source) == Some(&effector.id) {
+            self.effector_sources.remove(&effector.source);
+        }
+        if let Some(moved) = effectors.get(index) {
+            self.effectors.insert(moved.id, index);
+        }
+        effector
+    }
+
+    #[cfg(test)]
+    pub fn matches(&self, other: &Self) -> bool {
+        let sorted = |adjacency: &HashMap<NeuronID, Vec<NeuronID>>| {
+            adjacency
+                .iter()
+                .map(|(k, v)| {
+                    let mut v = v.clone();
+                    v.sort();
+                    (*k, v)
+                })
+                .collect::<HashMap<_, _>>()
+        };
+        self.neurons == other.neurons
+            && self.synapses == other.synapses
+            && sorted(&self.incoming) == sorted(&other.incoming)
+            && sorted(&self.outgoing) == sorted(&other.outgoing)
+            && self.sensors == other.sensors
+            && self.effectors == other.effectors
+            && self.sensor_targets == other.sensor_targets
+            && self.effector_sources == other.effector_sources
+    }
+
+    fn insert_synapse(&mut self, synapse: &Synapse, index: usize) {
+        self.synapses
+            .insert((synapse.source, synapse.target), index);
+        self.outgoing
+            .entry(synapse.source)
+            .or_default()
+            .push(synapse.target);
+        self.incoming
+            .entry(synapse.target)
+            .or_default()
+            .push(synapse.source);
+    }
+}
+
+// index is fully derived from brain storage so it never takes part in comparisons.
+impl PartialEq for BrainIndex {
+    #[inline]
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+impl fmt::Debug for BrainIndex {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("BrainIndex")
+            .field("neurons", &self.neurons.len())
+            .field("synapses", &self.synapses.len())
+            .field("sensors", &self.sensors.len())
+            .field("effectors", &self.effectors.len())
+            .finish()
+    }
+}
+
+fn remove_adjacent(
+    adjacency: &mut HashMap<NeuronID, Vec<NeuronID>>,
+    key: NeuronID,
+    value: NeuronID,
+) {
+    let empty = if let Some(list) = adjacency.get_mut(&key) {
+        if let Some(index) = list.iter().position(|id| *id == value) {
+            list.swap_remove(index);
+        }
+        list.is_empty()
+    } else {
+        false
+    };
+    if empty {
+        adjacency.remove(&key);
+    }
+}
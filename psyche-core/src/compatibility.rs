@@ -0,0 +1,182 @@
+use crate::brain::Brain;
+use crate::neuron::{Neuron, NeuronID};
+use crate::Scalar;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+
+/// Compatibility distance between brains, used to tell if they belong to the same species.
+/// Distance is weighted sum of:
+/// - ratio of genes (neurons and synapses) without homologous counterpart in other brain;
+/// - mean receptors difference of homologous synapses;
+/// - mean distance between homologous neurons.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Compatibility {
+    /// Max distance between neurons of both brains to consider them homologous.
+    pub homology_range: Scalar,
+    pub genes_weight: Scalar,
+    pub receptors_weight: Scalar,
+    pub spatial_weight: Scalar,
+}
+
+impl Default for Compatibility {
+    fn default() -> Self {
+        Self {
+            homology_range: 1.0,
+            genes_weight: 1.0,
+            receptors_weight: 0.5,
+            spatial_weight: 0.5,
+        }
+    }
+}
+
+impl Compatibility {
+    pub fn distance(&self, a: &Brain, b: &Brain) -> Scalar {
+        let pairs = align_neurons(a, b, self.homology_range);
+        let spatial = if pairs.is_empty() {
+            0.0
+        } else {
+            pairs
+                .iter()
+                .map(|(na, nb)| {
+                    let pa = a.neuron(*na).unwrap().position();
+                    let pb = b.neuron(*nb).unwrap().position();
+                    pa.distance(pb)
+                })
+                .sum::<Scalar>()
+                / pairs.len() as Scalar
+        };
+        let homologous = pairs.iter().cloned().collect::<HashMap<_, _>>();
+        let mut synapses = 0;
+        let mut receptors = 0.0;
+        for synapse in a.synapses() {
+            if let (Some(source), Some(target)) = (
+                homologous.get(&synapse.source()),
+                homologous.get(&synapse.target()),
+            ) {
+                if let Some(index) = b.index().synapse(*source, *target) {
+                    synapses += 1;
+                    receptors += (synapse.receptors() - b.synapses()[index].receptors()).abs();
+                }
+            }
+        }
+        let receptors = if synapses > 0 {
+            receptors / synapses as Scalar
+        } else {
+            0.0
+        };
+        let genes_a = a.neurons().len() + a.synapses().len();
+        let genes_b = b.neurons().len() + b.synapses().len();
+        let matching = pairs.len() + synapses;
+        let genes =
+            (genes_a + genes_b - matching * 2) as Scalar / genes_a.max(genes_b).max(1) as Scalar;
+        self.genes_weight * genes
+            + self.receptors_weight * receptors
+            + self.spatial_weight * spatial
+    }
+}
+
+/// Pairs of homologous neurons of both brains.
+/// Neurons of the same lineage are paired first, then targets of shared sensors and sources of
+/// shared effectors, then remaining neurons are paired with their closest counterparts within
+/// given range.
+pub fn align_neurons(a: &Brain, b: &Brain, range: Scalar) -> Vec<(NeuronID, NeuronID)> {
+    let mut result = vec![];
+    let mut used_a = HashSet::new();
+    let mut used_b = HashSet::new();
+
+    let mut lineages = HashMap::new();
+    for n in b.neurons() {
+        lineages.entry(n.lineage()).or_insert_with(|| n.id());
+    }
+    for n in a.neurons() {
+        if let Some(nb) = lineages.get(&n.lineage()) {
+            pair(n.id(), *nb, &mut used_a, &mut used_b, &mut result);
+        }
+    }
+    for id in a.get_sensors() {
+        if let (Some(sa), Some(sb)) = (a.sensor(id), b.sensor(id)) {
+            pair(
+                sa.target(),
+                sb.target(),
+                &mut used_a,
+                &mut used_b,
+                &mut result,
+            );
+        }
+    }
+    for id in a.get_effectors() {
+        if let (Some(ea), Some(eb)) = (a.effector(id), b.effector(id)) {
+            pair(
+                ea.source(),
+                eb.source(),
+                &mut used_a,
+                &mut used_b,
+                &mut result,
+            );
+        }
+    }
+
+    // only neurons left without counterpart are searched by position, bucketed into grid of
+    // cells as big as the range, so every neuron is compared only with its neighbor cells.
+    let size = range.max(Scalar::EPSILON);
+    let cell = |n: &Neuron| {
+        let p = n.position();
+        (
+            (p.x / size).floor() as i64,
+            (p.y / size).floor() as i64,
+            (p.z / size).floor() as i64,
+        )
+    };
+    let mut grid = HashMap::<_, Vec<&Neuron>>::new();
+    for nb in b.neurons() {
+        if !used_b.contains(&nb.id()) {
+            grid.entry(cell(nb)).or_default().push(nb);
+        }
+    }
+    let range_sqr = range * range;
+    let mut candidates = vec![];
+    for na in a.neurons() {
+        if used_a.contains(&na.id()) {
+            continue;
+        }
+        let (x, y, z) = cell(na);
+        for dx in -1..=1 {
+            for dy in -1..=1 {
+                for dz in -1..=1 {
+                    let key = (
+                        x.saturating_add(dx),
+                        y.saturating_add(dy),
+                        z.saturating_add(dz),
+                    );
+                    for nb in grid.get(&key).into_iter().flatten() {
+                        let distance = na.position().distance_sqr(nb.position());
+                        if distance <= range_sqr {
+                            candidates.push((distance, na.id(), nb.id()));
+                        }
+                    }
+                }
+            }
+        }
+    }
+    candidates.sort_by(|x, y| x.0.partial_cmp(&y.0).unwrap_or(Ordering::Equal));
+    for (_, na, nb) in candidates {
+        pair(na, nb, &mut used_a, &mut used_b, &mut result);
+    }
+    result
+}
+
+fn pair(
+    na: NeuronID,
+    nb: NeuronID,
+    used_a: &mut HashSet<NeuronID>,
+    used_b: &mut HashSet<NeuronID>,
+    result: &mut Vec<(NeuronID, NeuronID)>,
+) {
+    if !used_a.contains(&na) && !used_b.contains(&nb) {
+        used_a.insert(na);
+        used_b.insert(nb);
+        result.push((na, nb));
+    }
+}
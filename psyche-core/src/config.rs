@@ -1,4 +1,13 @@
+use crate::neuron_model::{
+    IzhikevichModel, IzhikevichMutation, LeakyIntegrateAndFireModel, LeakyIntegrateAndFireMutation,
+    NeuronModelType, NeuronModels, ThresholdModel,
+};
+use crate::plasticity::{
+    PlasticityRuleType, PlasticityRules, ReceptorsRule, StdpMutation, StdpRule,
+};
 use crate::Scalar;
+use rand::distributions::Normal;
+use rand::Rng;
 use serde::{Deserialize, Serialize};
 use std::ops::Range;
 
@@ -16,6 +25,25 @@ pub struct Config {
     pub synapse_overdose_receptors: Option<Scalar>,
     pub synapse_propagation_decay: Scalar,
     pub synapse_new_connection_receptors: Option<Scalar>,
+    #[serde(default)]
+    pub neuron_model: NeuronModelType,
+    #[serde(default)]
+    pub leaky_integrate_and_fire: LeakyIntegrateAndFireModel,
+    #[serde(default)]
+    pub izhikevich: IzhikevichModel,
+    #[serde(default)]
+    pub plasticity_rule: PlasticityRuleType,
+    #[serde(default)]
+    pub stdp: StdpRule,
+    /// Time after which synapse eligibility trace decays to ~37%.
+    #[serde(default = "default_eligibility_trace_time")]
+    pub eligibility_trace_time: Scalar,
+    /// Receptors change per unit of reward and eligibility.
+    #[serde(default = "default_reward_learning_rate")]
+    pub reward_learning_rate: Scalar,
+    /// Time window over which neurons firing rate is averaged.
+    #[serde(default = "default_firing_rate_time")]
+    pub firing_rate_time: Scalar,
 }
 
 impl Default for Config {
@@ -32,6 +60,14 @@ impl Default for Config {
             synapse_overdose_receptors: None,
             synapse_propagation_decay: 0.0,
             synapse_new_connection_receptors: None,
+            neuron_model: Default::default(),
+            leaky_integrate_and_fire: Default::default(),
+            izhikevich: Default::default(),
+            plasticity_rule: Default::default(),
+            stdp: Default::default(),
+            eligibility_trace_time: default_eligibility_trace_time(),
+            reward_learning_rate: default_reward_learning_rate(),
+            firing_rate_time: default_firing_rate_time(),
         }
     }
 }
@@ -95,10 +131,252 @@ impl Config {
                 (None, Some(b)) => Some(b),
                 _ => None,
             },
+            neuron_model: self.neuron_model,
+            leaky_integrate_and_fire: self
+                .leaky_integrate_and_fire
+                .merge(&other.leaky_integrate_and_fire),
+            izhikevich: self.izhikevich.merge(&other.izhikevich),
+            plasticity_rule: self.plasticity_rule,
+            stdp: self.stdp.merge(&other.stdp),
+            eligibility_trace_time: merge_scalar(
+                self.eligibility_trace_time,
+                other.eligibility_trace_time,
+            ),
+            reward_learning_rate: merge_scalar(
+                self.reward_learning_rate,
+                other.reward_learning_rate,
+            ),
+            firing_rate_time: merge_scalar(self.firing_rate_time, other.firing_rate_time),
+        }
+    }
+
+    /// Returns copy of config with fields perturbed by given mutation.
+    pub fn mutate<R>(&self, mutation: &ConfigMutation, rng: &mut R) -> Self
+    where
+        R: Rng,
+    {
+        let option = |m: &Option<OptionMutation>, value: Option<Scalar>, rng: &mut R| match m {
+            Some(m) => m.mutate(value, rng),
+            None => value,
+        };
+        let mut result = self.clone();
+        result.propagation_speed =
+            mutate_scalar(&mutation.propagation_speed, self.propagation_speed, rng);
+        result.neuron_potential_decay = mutate_scalar(
+            &mutation.neuron_potential_decay,
+            self.neuron_potential_decay,
+            rng,
+        );
+        result.action_potential_treshold = mutate_scalar(
+            &mutation.action_potential_treshold,
+            self.action_potential_treshold,
+            rng,
+        );
+        result.receptors_excitation = mutate_scalar(
+            &mutation.receptors_excitation,
+            self.receptors_excitation,
+            rng,
+        );
+        result.receptors_inhibition = mutate_scalar(
+            &mutation.receptors_inhibition,
+            self.receptors_inhibition,
+            rng,
+        );
+        let start = mutate_scalar(
+            &mutation.default_receptors,
+            self.default_receptors.start,
+            rng,
+        );
+        let end = mutate_scalar(&mutation.default_receptors, self.default_receptors.end, rng);
+        // empty range cannot be sampled, so it is not allowed.
+        if start != end {
+            result.default_receptors = start.min(end)..start.max(end);
+        }
+        result.synapse_inactivity_time = mutate_scalar(
+            &mutation.synapse_inactivity_time,
+            self.synapse_inactivity_time,
+            rng,
+        );
+        result.synapse_reconnection_range = option(
+            &mutation.synapse_reconnection_range,
+            self.synapse_reconnection_range,
+            rng,
+        );
+        result.synapse_overdose_receptors = option(
+            &mutation.synapse_overdose_receptors,
+            self.synapse_overdose_receptors,
+            rng,
+        );
+        result.synapse_propagation_decay = mutate_scalar(
+            &mutation.synapse_propagation_decay,
+            self.synapse_propagation_decay,
+            rng,
+        );
+        result.synapse_new_connection_receptors = option(
+            &mutation.synapse_new_connection_receptors,
+            self.synapse_new_connection_receptors,
+            rng,
+        );
+        result.leaky_integrate_and_fire = self
+            .leaky_integrate_and_fire
+            .mutate(&mutation.leaky_integrate_and_fire, rng);
+        result.izhikevich = self.izhikevich.mutate(&mutation.izhikevich, rng);
+        result.stdp = self.stdp.mutate(&mutation.stdp, rng);
+        result.eligibility_trace_time = mutate_scalar(
+            &mutation.eligibility_trace_time,
+            self.eligibility_trace_time,
+            rng,
+        );
+        result.reward_learning_rate = mutate_scalar(
+            &mutation.reward_learning_rate,
+            self.reward_learning_rate,
+            rng,
+        );
+        result.firing_rate_time =
+            mutate_scalar(&mutation.firing_rate_time, self.firing_rate_time, rng);
+        result
+    }
+
+    pub(crate) fn neuron_models(&self) -> NeuronModels {
+        NeuronModels {
+            default: self.neuron_model,
+            threshold: ThresholdModel {
+                potential_decay: self.neuron_potential_decay,
+                action_potential_treshold: self.action_potential_treshold,
+            },
+            leaky_integrate_and_fire: self.leaky_integrate_and_fire,
+            izhikevich: self.izhikevich,
+        }
+    }
+
+    pub(crate) fn plasticity_rules(&self) -> PlasticityRules {
+        PlasticityRules {
+            rule: self.plasticity_rule,
+            receptors: ReceptorsRule {
+                receptors_excitation: self.receptors_excitation,
+            },
+            stdp: self.stdp,
+        }
+    }
+}
+
+/// Gaussian perturbation of config value.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScalarMutation {
+    /// Probability (0..1) of value being perturbed.
+    pub rate: Scalar,
+    /// Standard deviation of perturbation.
+    pub deviation: Scalar,
+    /// Bounds of perturbed value.
+    pub range: Range<Scalar>,
+}
+
+impl ScalarMutation {
+    #[inline]
+    pub fn new(rate: Scalar, deviation: Scalar, range: Range<Scalar>) -> Self {
+        Self {
+            rate,
+            deviation,
+            range,
+        }
+    }
+
+    pub fn mutate<R>(&self, value: Scalar, rng: &mut R) -> Scalar
+    where
+        R: Rng,
+    {
+        if self.deviation > 0.0 && rng.gen::<Scalar>() < self.rate {
+            let value = value + rng.sample(Normal::new(0.0, self.deviation));
+            value.max(self.range.start).min(self.range.end)
+        } else {
+            value
+        }
+    }
+}
+
+/// Mutation of optional config value: toggling it on and off, and perturbing it when on.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OptionMutation {
+    /// Probability (0..1) of value being toggled.
+    /// Value toggled on is picked randomly from perturbation bounds.
+    pub toggle_rate: Scalar,
+    pub value: ScalarMutation,
+}
+
+impl OptionMutation {
+    #[inline]
+    pub fn new(toggle_rate: Scalar, value: ScalarMutation) -> Self {
+        Self { toggle_rate, value }
+    }
+
+    pub fn mutate<R>(&self, value: Option<Scalar>, rng: &mut R) -> Option<Scalar>
+    where
+        R: Rng,
+    {
+        if rng.gen::<Scalar>() < self.toggle_rate {
+            match value {
+                Some(_) => None,
+                None if self.value.range.start < self.value.range.end => {
+                    Some(rng.gen_range(self.value.range.start, self.value.range.end))
+                }
+                None => Some(self.value.range.start),
+            }
+        } else {
+            value.map(|value| self.value.mutate(value, rng))
         }
     }
 }
 
-fn merge_scalar(a: Scalar, b: Scalar) -> Scalar {
+/// Per-field mutation of brain config; fields without mutation are kept unchanged.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ConfigMutation {
+    pub propagation_speed: Option<ScalarMutation>,
+    pub neuron_potential_decay: Option<ScalarMutation>,
+    pub action_potential_treshold: Option<ScalarMutation>,
+    pub receptors_excitation: Option<ScalarMutation>,
+    pub receptors_inhibition: Option<ScalarMutation>,
+    /// Perturbs both ends of range independently.
+    pub default_receptors: Option<ScalarMutation>,
+    pub synapse_inactivity_time: Option<ScalarMutation>,
+    pub synapse_reconnection_range: Option<OptionMutation>,
+    pub synapse_overdose_receptors: Option<OptionMutation>,
+    pub synapse_propagation_decay: Option<ScalarMutation>,
+    pub synapse_new_connection_receptors: Option<OptionMutation>,
+    pub leaky_integrate_and_fire: LeakyIntegrateAndFireMutation,
+    pub izhikevich: IzhikevichMutation,
+    pub stdp: StdpMutation,
+    pub eligibility_trace_time: Option<ScalarMutation>,
+    pub reward_learning_rate: Option<ScalarMutation>,
+    pub firing_rate_time: Option<ScalarMutation>,
+}
+
+fn default_eligibility_trace_time() -> Scalar {
+    1.0
+}
+
+fn default_reward_learning_rate() -> Scalar {
+    0.1
+}
+
+fn default_firing_rate_time() -> Scalar {
+    1.0
+}
+
+pub(crate) fn merge_scalar(a: Scalar, b: Scalar) -> Scalar {
     (a + b) * 0.5
 }
+
+pub(crate) fn mutate_scalar<R>(
+    mutation: &Option<ScalarMutation>,
+    value: Scalar,
+    rng: &mut R,
+) -> Scalar
+where
+    R: Rng,
+{
+    match mutation {
+        Some(mutation) => mutation.mutate(value, rng),
+        None => value,
+    }
+}
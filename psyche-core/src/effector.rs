@@ -1,13 +1,118 @@
 use crate::id::ID;
+use crate::label::Label;
 use crate::neuron::NeuronID;
 use crate::Scalar;
 use serde::{Deserialize, Serialize};
 
 pub type EffectorID = ID<Effector>;
 
+/// Describes how source neuron activity is turned into effector output.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub enum EffectorMode {
+    /// Latches raw potential of source neuron.
+    #[default]
+    Potential,
+    /// Number of source neuron spikes within last `window` of time.
+    SpikeCount { window: Scalar },
+    /// Exponential moving average of source neuron potential.
+    MovingAverage { time_constant: Scalar },
+    /// Holds highest source neuron potential, lowering it by `decay` per second.
+    MaxHold { decay: Scalar },
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Effector {
     pub(crate) id: EffectorID,
     pub(crate) source: NeuronID,
     pub(crate) potential: Scalar,
+    #[serde(default)]
+    pub(crate) mode: EffectorMode,
+    #[serde(default)]
+    pub(crate) spikes: Vec<Scalar>,
+    #[serde(default)]
+    pub(crate) label: Label,
+}
+
+impl Effector {
+    pub(crate) fn new(id: EffectorID, source: NeuronID, mode: EffectorMode) -> Self {
+        Self {
+            id,
+            source,
+            potential: 0.0,
+            mode,
+            spikes: vec![],
+            label: Default::default(),
+        }
+    }
+
+    #[inline]
+    pub fn id(&self) -> EffectorID {
+        self.id
+    }
+
+    #[inline]
+    pub fn source(&self) -> NeuronID {
+        self.source
+    }
+
+    #[inline]
+    pub fn mode(&self) -> EffectorMode {
+        self.mode
+    }
+
+    #[inline]
+    pub fn label(&self) -> &Label {
+        &self.label
+    }
+
+    /// Current output of effector.
+    #[inline]
+    pub fn potential(&self) -> Scalar {
+        self.potential
+    }
+
+    pub(crate) fn process(
+        &mut self,
+        potential: Scalar,
+        fired: bool,
+        time: Scalar,
+        delta_time: Scalar,
+    ) {
+        match self.mode {
+            EffectorMode::Potential => self.potential = potential,
+            EffectorMode::SpikeCount { window } => {
+                if fired {
+                    self.spikes.push(time);
+                }
+                self.spikes.retain(|t| time - *t < window);
+                self.potential = self.spikes.len() as Scalar;
+            }
+            EffectorMode::MovingAverage { time_constant } => {
+                let factor = if time_constant > 0.0 {
+                    1.0 - (-delta_time / time_constant).exp()
+                } else {
+                    1.0
+                };
+                self.potential += (potential - self.potential) * factor;
+            }
+            EffectorMode::MaxHold { decay } => {
+                self.potential = (self.potential - decay * delta_time).max(potential);
+            }
+        }
+    }
+
+    /// Takes current output. Only latched raw potential is cleared, other modes keep their state
+    /// so that they carry on accumulating across reads.
+    pub(crate) fn release(&mut self) -> Scalar {
+        let potential = self.potential;
+        if self.mode == EffectorMode::Potential {
+            self.potential = 0.0;
+        }
+        potential
+    }
+
+    pub(crate) fn reset(&mut self) {
+        self.potential = 0.0;
+        self.spikes.clear();
+    }
 }
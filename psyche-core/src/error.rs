@@ -1,3 +1,4 @@
+use crate::brain::BrainID;
 use crate::effector::EffectorID;
 use crate::neuron::NeuronID;
 use crate::sensor::SensorID;
@@ -16,6 +17,27 @@ pub enum Error {
     BindingEffectorToNeuron(EffectorID, NeuronID),
     NeuronIsAlreadyConnectedToSensor(NeuronID, SensorID),
     NeuronIsAlreadyConnectedToEffector(NeuronID, EffectorID),
+    DuplicatedNeuron(NeuronID),
+    NeuronHasInvalidOwner(NeuronID, BrainID),
+    NeuronHasInvalidPotential(NeuronID),
+    NeuronHasInvalidState(NeuronID),
+    SynapseNeuronDoesNotExists(NeuronID, NeuronID),
+    SynapseBindsNeuronToItSelf(NeuronID),
+    DuplicatedSynapse(NeuronID, NeuronID),
+    SynapseHasInvalidValues(NeuronID, NeuronID),
+    SensorTargetDoesNotExists(SensorID, NeuronID),
+    EffectorSourceDoesNotExists(EffectorID, NeuronID),
+    EffectorHasInvalidPotential(EffectorID),
+    DuplicatedSensor(SensorID),
+    DuplicatedEffector(EffectorID),
+    SensorHasInvalidState(SensorID),
+    NeuronHasMultipleSensors(NeuronID),
+    NeuronHasMultipleEffectors(NeuronID),
+    NeuronIsBothSensorAndEffector(NeuronID),
+    // (expected, provided)
+    InputsCountMismatch(usize, usize),
+    // (expected, provided)
+    OutputsCountMismatch(usize, usize),
 }
 
 impl Error {
@@ -0,0 +1,20 @@
+use crate::effector::EffectorID;
+use crate::neuron::NeuronID;
+use crate::sensor::SensorID;
+use crate::Scalar;
+use serde::{Deserialize, Serialize};
+
+/// Things that happened inside brain, collected when events are enabled.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub enum BrainEvent {
+    NeuronFired(NeuronID),
+    /// (from, to, potential)
+    ImpulseDelivered(NeuronID, NeuronID, Scalar),
+    /// (from, to)
+    SynapseCreated(NeuronID, NeuronID),
+    /// (from, to)
+    SynapseInhibited(NeuronID, NeuronID),
+    NeuronDied(NeuronID),
+    SensorRemoved(SensorID),
+    EffectorRemoved(EffectorID),
+}
@@ -0,0 +1,154 @@
+use crate::brain::Brain;
+use crate::effector::EffectorID;
+use crate::neuron::NeuronID;
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// Neurons that receive input from at least one sensor (including sensor targets).
+pub fn reachable_from_sensors(brain: &Brain) -> HashSet<NeuronID> {
+    let sources = brain
+        .get_sensors()
+        .into_iter()
+        .filter_map(|id| brain.sensor(id).map(|s| s.target()))
+        .collect::<Vec<_>>();
+    brain
+        .index()
+        .distances_from(&sources)
+        .keys()
+        .cloned()
+        .collect()
+}
+
+/// Neurons that pass their output to at least one effector (including effector sources).
+pub fn reaching_effectors(brain: &Brain) -> HashSet<NeuronID> {
+    let index = brain.index();
+    let mut result = brain
+        .get_effectors()
+        .into_iter()
+        .filter_map(|id| brain.effector(id).map(|e| e.source()))
+        .collect::<HashSet<_>>();
+    let mut queue = result.iter().cloned().collect::<VecDeque<_>>();
+    while let Some(id) = queue.pop_front() {
+        for other in index.incoming(id) {
+            if result.insert(*other) {
+                queue.push_back(*other);
+            }
+        }
+    }
+    result
+}
+
+/// Effectors that no sensor can reach.
+pub fn unreachable_effectors(brain: &Brain) -> Vec<EffectorID> {
+    let reachable = reachable_from_sensors(brain);
+    brain
+        .get_effectors()
+        .into_iter()
+        .filter_map(|id| brain.effector(id))
+        .filter(|e| !reachable.contains(&e.source()))
+        .map(|e| e.id())
+        .collect()
+}
+
+/// Neurons that never receive input from any sensor.
+pub fn unreachable_neurons(brain: &Brain) -> Vec<NeuronID> {
+    let reachable = reachable_from_sensors(brain);
+    brain
+        .neurons()
+        .iter()
+        .map(|n| n.id())
+        .filter(|id| !reachable.contains(id))
+        .collect()
+}
+
+/// Neurons that do not lie on any path from sensor to effector, so they never affect output.
+pub fn dead_neurons(brain: &Brain) -> Vec<NeuronID> {
+    let reachable = reachable_from_sensors(brain);
+    let reaching = reaching_effectors(brain);
+    brain
+        .neurons()
+        .iter()
+        .map(|n| n.id())
+        .filter(|id| !reachable.contains(id) || !reaching.contains(id))
+        .collect()
+}
+
+/// Dominator sets of neurons reachable from sensors: every path from any sensor
+/// to given neuron passes through all neurons of its set (neuron itself included).
+pub fn dominators(brain: &Brain) -> HashMap<NeuronID, HashSet<NeuronID>> {
+    let index = brain.index();
+    let sources = brain
+        .get_sensors()
+        .into_iter()
+        .filter_map(|id| brain.sensor(id).map(|s| s.target()))
+        .collect::<HashSet<_>>();
+    let mut order = index
+        .distances_from(&sources.iter().cloned().collect::<Vec<_>>())
+        .into_iter()
+        .collect::<Vec<_>>();
+    order.sort_by_key(|(id, distance)| (*distance, *id));
+    let order = order.into_iter().map(|(id, _)| id).collect::<Vec<_>>();
+    let all = order.iter().cloned().collect::<HashSet<_>>();
+    let mut result = order
+        .iter()
+        .map(|id| {
+            if sources.contains(id) {
+                (*id, std::iter::once(*id).collect::<HashSet<_>>())
+            } else {
+                (*id, all.clone())
+            }
+        })
+        .collect::<HashMap<_, _>>();
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for id in &order {
+            if sources.contains(id) {
+                continue;
+            }
+            let mut dominators = index
+                .incoming(*id)
+                .iter()
+                .filter_map(|other| result.get(other))
+                .fold(None, |accum: Option<HashSet<NeuronID>>, set| match accum {
+                    Some(accum) => Some(accum.intersection(set).cloned().collect()),
+                    None => Some(set.clone()),
+                })
+                .unwrap_or_default();
+            dominators.insert(*id);
+            if dominators != result[id] {
+                result.insert(*id, dominators);
+                changed = true;
+            }
+        }
+    }
+    result
+}
+
+/// Connects every effector that no sensor can reach with closest neuron that receives input,
+/// falling back to further neurons when binding fails. Effectors stay unreachable when there is
+/// no such neuron that can be bound to them.
+/// Returns number of created synapses.
+pub(crate) fn connect_unreachable_effectors(brain: &mut Brain) -> usize {
+    let mut count = 0;
+    for id in unreachable_effectors(brain) {
+        let source = brain.effector(id).unwrap().source();
+        let position = brain.neuron(source).unwrap().position();
+        let reachable = reachable_from_sensors(brain);
+        let mut candidates = brain
+            .neurons()
+            .iter()
+            .filter(|n| {
+                reachable.contains(&n.id()) && brain.index().neuron_effector(n.id()).is_none()
+            })
+            .map(|n| (n.id(), n.position().distance_sqr(position)))
+            .collect::<Vec<_>>();
+        candidates.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
+        for (from, _) in candidates {
+            if let Ok(Some(_)) = brain.bind_neurons(from, source) {
+                count += 1;
+                break;
+            }
+        }
+    }
+    count
+}
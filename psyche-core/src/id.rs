@@ -1,3 +1,4 @@
+use rand::Rng;
 use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
 use std::fmt;
@@ -30,6 +31,18 @@ impl<T> ID<T> {
         }
     }
 
+    /// Creates new identifier (uuidv4) from random number generator.
+    pub fn from_rng<R>(rng: &mut R) -> Self
+    where
+        R: Rng + ?Sized,
+    {
+        let mut bytes = [0; 16];
+        rng.fill(&mut bytes);
+        bytes[6] = (bytes[6] & 0x0f) | 0x40;
+        bytes[8] = (bytes[8] & 0x3f) | 0x80;
+        Self::from_bytes(bytes)
+    }
+
     /// Gets underlying UUID object.
     #[inline]
     pub fn uuid(&self) -> Uuid {
@@ -0,0 +1,44 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Host-defined description of sensor or effector, serialized together with brain.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Label {
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub metadata: BTreeMap<String, String>,
+}
+
+impl Label {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn name(mut self, value: &str) -> Self {
+        self.name = Some(value.to_owned());
+        self
+    }
+
+    pub fn tag(mut self, value: &str) -> Self {
+        self.tags.push(value.to_owned());
+        self
+    }
+
+    pub fn meta(mut self, key: &str, value: &str) -> Self {
+        self.metadata.insert(key.to_owned(), value.to_owned());
+        self
+    }
+
+    #[inline]
+    pub fn has_name(&self, name: &str) -> bool {
+        self.name.as_deref() == Some(name)
+    }
+
+    #[inline]
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t == tag)
+    }
+}
@@ -9,12 +9,23 @@ pub mod tests;
 
 pub mod brain;
 pub mod brain_builder;
+pub(crate) mod brain_index;
+pub mod compatibility;
 pub mod config;
 pub mod effector;
 pub mod error;
+pub mod event;
+pub mod graph;
 pub mod id;
+pub mod label;
+pub mod lineage;
 pub mod neuron;
+pub mod neuron_model;
 pub mod offspring_builder;
+pub mod plasticity;
+pub mod recorder;
+pub mod rng;
 pub mod sensor;
+pub mod topology;
 
 pub type Scalar = f64;
@@ -0,0 +1,39 @@
+use crate::id::ID;
+use crate::neuron::NeuronID;
+use serde::{Deserialize, Deserializer};
+
+/// Marker of lineage identifiers.
+#[derive(Debug, Copy, Clone)]
+pub struct Lineage;
+
+/// Innovation number shared by homologous neurons and synapses of related brains.
+/// Neuron created from scratch gets lineage equal to its ID, offspring neurons inherit lineage
+/// of their ancestors, so it survives duplication, merging and serialization.
+pub type LineageID = ID<Lineage>;
+
+impl LineageID {
+    /// Lineage of neuron created from scratch.
+    #[inline]
+    pub fn from_neuron(id: NeuronID) -> Self {
+        Self::from_bytes(*id.uuid().as_bytes())
+    }
+
+    /// Lineage of synapse between neurons of given lineages.
+    /// Synapses connecting homologous neurons of different brains share their lineage, even if
+    /// they were created independently.
+    pub fn from_synapse(source: Self, target: Self) -> Self {
+        let source = u128::from_le_bytes(*source.uuid().as_bytes());
+        let target = u128::from_le_bytes(*target.uuid().as_bytes());
+        let value =
+            source.rotate_left(64) ^ target.wrapping_mul(0x9e37_79b9_7f4a_7c15_f39c_c060_5ced_c835);
+        Self::from_bytes(value.to_le_bytes())
+    }
+}
+
+// reads lineage stored in plain form, so only snapshots missing it entirely give `None`.
+pub(crate) fn deserialize_lineage<'de, D>(deserializer: D) -> Result<Option<LineageID>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    LineageID::deserialize(deserializer).map(Some)
+}
@@ -1,7 +1,10 @@
 use crate::brain::BrainID;
 use crate::id::ID;
+use crate::lineage::{deserialize_lineage, LineageID};
+use crate::neuron_model::{NeuronModel, NeuronModelType, NeuronState};
 use crate::Scalar;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 pub type NeuronID = ID<Neuron>;
 
@@ -13,13 +16,101 @@ pub struct Impulse {
 }
 
 #[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
-pub(crate) struct Synapse {
-    pub source: NeuronID,
-    pub target: NeuronID,
-    pub distance: Scalar,
-    pub receptors: Scalar,
-    pub impulses: Vec<Impulse>,
-    pub inactivity: Scalar,
+pub struct Synapse {
+    pub(crate) source: NeuronID,
+    pub(crate) target: NeuronID,
+    pub(crate) distance: Scalar,
+    pub(crate) receptors: Scalar,
+    pub(crate) impulses: Vec<Impulse>,
+    pub(crate) inactivity: Scalar,
+    #[serde(default)]
+    pub(crate) inhibitory: bool,
+    #[serde(default)]
+    pub(crate) eligibility: Scalar,
+    pub(crate) lineage: LineageID,
+}
+
+// serialized form of synapse, lineage gets restored from its neurons lineages when missing.
+#[derive(Deserialize)]
+pub(crate) struct SynapseData {
+    source: NeuronID,
+    target: NeuronID,
+    distance: Scalar,
+    receptors: Scalar,
+    impulses: Vec<Impulse>,
+    inactivity: Scalar,
+    #[serde(default)]
+    inhibitory: bool,
+    #[serde(default)]
+    eligibility: Scalar,
+    #[serde(default, deserialize_with = "deserialize_lineage")]
+    lineage: Option<LineageID>,
+}
+
+impl SynapseData {
+    pub(crate) fn into_synapse(self, lineages: &HashMap<NeuronID, LineageID>) -> Synapse {
+        let lineage = match self.lineage {
+            Some(lineage) => lineage,
+            None => {
+                let lineage_of = |id: NeuronID| {
+                    lineages
+                        .get(&id)
+                        .cloned()
+                        .unwrap_or_else(|| LineageID::from_neuron(id))
+                };
+                LineageID::from_synapse(lineage_of(self.source), lineage_of(self.target))
+            }
+        };
+        Synapse {
+            source: self.source,
+            target: self.target,
+            distance: self.distance,
+            receptors: self.receptors,
+            impulses: self.impulses,
+            inactivity: self.inactivity,
+            inhibitory: self.inhibitory,
+            eligibility: self.eligibility,
+            lineage,
+        }
+    }
+}
+
+impl Synapse {
+    #[inline]
+    pub fn source(&self) -> NeuronID {
+        self.source
+    }
+
+    #[inline]
+    pub fn target(&self) -> NeuronID {
+        self.target
+    }
+
+    #[inline]
+    pub fn distance(&self) -> Scalar {
+        self.distance
+    }
+
+    #[inline]
+    pub fn receptors(&self) -> Scalar {
+        self.receptors
+    }
+
+    /// Impulses that currently travel through synapse.
+    #[inline]
+    pub fn impulses(&self) -> &[Impulse] {
+        &self.impulses
+    }
+
+    #[inline]
+    pub fn is_inhibitory(&self) -> bool {
+        self.inhibitory
+    }
+
+    #[inline]
+    pub fn lineage(&self) -> LineageID {
+        self.lineage
+    }
 }
 
 #[derive(Debug, Default, Copy, Clone, PartialEq, Serialize, Deserialize)]
@@ -61,24 +152,78 @@ pub struct Neuron {
     owner_id: BrainID,
     position: Position,
     potential: Scalar,
+    #[serde(default)]
+    recovery: Scalar,
+    #[serde(default)]
+    refractory: Scalar,
+    #[serde(default)]
+    model: Option<NeuronModelType>,
+    #[serde(default)]
+    inhibitory: bool,
+    #[serde(default)]
+    last_spike: Option<Scalar>,
+    #[serde(default)]
+    firing_rate: Scalar,
+    lineage: LineageID,
 }
 
-impl Neuron {
-    pub(crate) fn new(owner_id: BrainID, position: Position) -> Self {
+// serialized form of neuron, lineage of neuron created from scratch is used when missing.
+#[derive(Deserialize)]
+pub(crate) struct NeuronData {
+    id: NeuronID,
+    owner_id: BrainID,
+    position: Position,
+    potential: Scalar,
+    #[serde(default)]
+    recovery: Scalar,
+    #[serde(default)]
+    refractory: Scalar,
+    #[serde(default)]
+    model: Option<NeuronModelType>,
+    #[serde(default)]
+    inhibitory: bool,
+    #[serde(default)]
+    last_spike: Option<Scalar>,
+    #[serde(default)]
+    firing_rate: Scalar,
+    #[serde(default, deserialize_with = "deserialize_lineage")]
+    lineage: Option<LineageID>,
+}
+
+impl From<NeuronData> for Neuron {
+    fn from(data: NeuronData) -> Self {
         Self {
-            id: Default::default(),
-            owner_id,
-            position,
-            potential: 0.0,
+            id: data.id,
+            owner_id: data.owner_id,
+            position: data.position,
+            potential: data.potential,
+            recovery: data.recovery,
+            refractory: data.refractory,
+            model: data.model,
+            inhibitory: data.inhibitory,
+            last_spike: data.last_spike,
+            firing_rate: data.firing_rate,
+            lineage: data
+                .lineage
+                .unwrap_or_else(|| LineageID::from_neuron(data.id)),
         }
     }
+}
 
+impl Neuron {
     pub(crate) fn with_id(id: NeuronID, owner_id: BrainID, position: Position) -> Self {
         Self {
             id,
             owner_id,
             position,
             potential: 0.0,
+            recovery: 0.0,
+            refractory: 0.0,
+            model: None,
+            inhibitory: false,
+            last_spike: None,
+            firing_rate: 0.0,
+            lineage: LineageID::from_neuron(id),
         }
     }
 
@@ -97,29 +242,107 @@ impl Neuron {
         self.position
     }
 
+    #[inline]
+    pub(crate) fn set_position(&mut self, position: Position) {
+        self.position = position;
+    }
+
     #[inline]
     pub fn potential(&self) -> Scalar {
         self.potential
     }
 
     #[inline]
-    pub(crate) fn push_potential(&mut self, value: Scalar) {
-        self.potential += value;
+    pub fn state(&self) -> NeuronState {
+        NeuronState {
+            potential: self.potential,
+            recovery: self.recovery,
+            refractory: self.refractory,
+        }
     }
 
     #[inline]
-    pub(crate) fn process_potential(&mut self, delta_time_times_decay: Scalar) {
-        if self.potential < -delta_time_times_decay {
-            self.potential = (self.potential + delta_time_times_decay).min(0.0);
-        } else if self.potential > delta_time_times_decay {
-            self.potential = (self.potential - delta_time_times_decay).max(0.0);
-        } else {
-            self.potential = 0.0;
-        }
+    pub(crate) fn set_state(&mut self, state: NeuronState) {
+        self.potential = state.potential;
+        self.recovery = state.recovery;
+        self.refractory = state.refractory;
+    }
+
+    #[inline]
+    pub fn lineage(&self) -> LineageID {
+        self.lineage
+    }
+
+    #[inline]
+    pub(crate) fn set_lineage(&mut self, lineage: LineageID) {
+        self.lineage = lineage;
+    }
+
+    /// Neuron model that overrides brain default model.
+    #[inline]
+    pub fn model(&self) -> Option<NeuronModelType> {
+        self.model
+    }
+
+    #[inline]
+    pub(crate) fn set_model(&mut self, model: Option<NeuronModelType>) {
+        self.model = model;
+    }
+
+    /// Tells if impulses fired by this neuron subtract potential from their targets.
+    #[inline]
+    pub fn is_inhibitory(&self) -> bool {
+        self.inhibitory
+    }
+
+    #[inline]
+    pub(crate) fn set_inhibitory(&mut self, value: bool) {
+        self.inhibitory = value;
+    }
+
+    /// Brain time at which neuron fired last time.
+    #[inline]
+    pub fn last_spike(&self) -> Option<Scalar> {
+        self.last_spike
+    }
+
+    #[inline]
+    pub(crate) fn set_last_spike(&mut self, time: Option<Scalar>) {
+        self.last_spike = time;
+    }
+
+    /// Fires per unit of time, averaged over `Config::firing_rate_time`.
+    #[inline]
+    pub fn firing_rate(&self) -> Scalar {
+        self.firing_rate
+    }
+
+    #[inline]
+    pub(crate) fn update_firing_rate(&mut self, decay: Scalar, spike: Scalar) {
+        self.firing_rate = self.firing_rate * decay + spike;
+    }
+
+    #[inline]
+    pub(crate) fn receive(&mut self, model: &dyn NeuronModel, value: Scalar) {
+        let mut state = self.state();
+        model.receive(&mut state, value);
+        self.set_state(state);
+    }
+
+    #[inline]
+    pub(crate) fn process(
+        &mut self,
+        model: &dyn NeuronModel,
+        delta_time: Scalar,
+    ) -> Option<Scalar> {
+        let mut state = self.state();
+        let result = model.process(&mut state, delta_time);
+        self.set_state(state);
+        result
     }
 
     #[inline]
     pub(crate) fn fire(&mut self) {
-        self.potential = 0.0;
+        self.set_state(Default::default());
     }
 }
@@ -0,0 +1,308 @@
+use crate::config::{merge_scalar, mutate_scalar, ScalarMutation};
+use crate::Scalar;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+/// Dynamic state of single neuron that is integrated by neuron models.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[repr(C)]
+pub struct NeuronState {
+    /// Membrane potential (relative to resting potential).
+    pub potential: Scalar,
+    /// Recovery variable (used by Izhikevich model).
+    pub recovery: Scalar,
+    /// Time left until neuron can receive and fire again.
+    pub refractory: Scalar,
+}
+
+/// Describes how neuron integrates incoming potential and when it fires.
+pub trait NeuronModel {
+    /// Called when impulse reaches neuron.
+    fn receive(&self, state: &mut NeuronState, potential: Scalar) {
+        state.potential += potential;
+    }
+
+    /// Integrates neuron state over time.
+    /// Returns potential sent to outgoing synapses when neuron fires.
+    fn process(&self, state: &mut NeuronState, delta_time: Scalar) -> Option<Scalar>;
+}
+
+/// Selects neuron model used to process neurons.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[repr(C)]
+pub enum NeuronModelType {
+    #[default]
+    Threshold,
+    LeakyIntegrateAndFire,
+    Izhikevich,
+}
+
+/// Linear potential decay with fire and reset on reaching action potential treshold.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[repr(C)]
+pub struct ThresholdModel {
+    pub potential_decay: Scalar,
+    pub action_potential_treshold: Scalar,
+}
+
+impl Default for ThresholdModel {
+    fn default() -> Self {
+        Self {
+            potential_decay: 1.0,
+            action_potential_treshold: 1.0,
+        }
+    }
+}
+
+impl NeuronModel for ThresholdModel {
+    fn process(&self, state: &mut NeuronState, delta_time: Scalar) -> Option<Scalar> {
+        let potential = state.potential;
+        let fired = if potential >= self.action_potential_treshold {
+            state.potential = 0.0;
+            Some(potential)
+        } else {
+            None
+        };
+        let dtpd = delta_time * self.potential_decay;
+        if state.potential < -dtpd {
+            state.potential = (state.potential + dtpd).min(0.0);
+        } else if state.potential > dtpd {
+            state.potential = (state.potential - dtpd).max(0.0);
+        } else {
+            state.potential = 0.0;
+        }
+        fired
+    }
+}
+
+/// Leaky integrate-and-fire with exponential decay and refractory period.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[repr(C)]
+pub struct LeakyIntegrateAndFireModel {
+    /// Membrane time constant (time after which potential decays to ~37%).
+    pub time_constant: Scalar,
+    pub action_potential_treshold: Scalar,
+    pub reset_potential: Scalar,
+    pub refractory_period: Scalar,
+}
+
+impl Default for LeakyIntegrateAndFireModel {
+    fn default() -> Self {
+        Self {
+            time_constant: 1.0,
+            action_potential_treshold: 1.0,
+            reset_potential: 0.0,
+            refractory_period: 0.05,
+        }
+    }
+}
+
+impl LeakyIntegrateAndFireModel {
+    pub fn merge(&self, other: &Self) -> Self {
+        Self {
+            time_constant: merge_scalar(self.time_constant, other.time_constant),
+            action_potential_treshold: merge_scalar(
+                self.action_potential_treshold,
+                other.action_potential_treshold,
+            ),
+            reset_potential: merge_scalar(self.reset_potential, other.reset_potential),
+            refractory_period: merge_scalar(self.refractory_period, other.refractory_period),
+        }
+    }
+
+    pub fn mutate<R>(&self, mutation: &LeakyIntegrateAndFireMutation, rng: &mut R) -> Self
+    where
+        R: Rng,
+    {
+        Self {
+            time_constant: mutate_scalar(&mutation.time_constant, self.time_constant, rng),
+            action_potential_treshold: mutate_scalar(
+                &mutation.action_potential_treshold,
+                self.action_potential_treshold,
+                rng,
+            ),
+            reset_potential: mutate_scalar(&mutation.reset_potential, self.reset_potential, rng),
+            refractory_period: mutate_scalar(
+                &mutation.refractory_period,
+                self.refractory_period,
+                rng,
+            ),
+        }
+    }
+}
+
+/// Per-field mutation of leaky integrate-and-fire model.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LeakyIntegrateAndFireMutation {
+    pub time_constant: Option<ScalarMutation>,
+    pub action_potential_treshold: Option<ScalarMutation>,
+    pub reset_potential: Option<ScalarMutation>,
+    pub refractory_period: Option<ScalarMutation>,
+}
+
+impl NeuronModel for LeakyIntegrateAndFireModel {
+    fn receive(&self, state: &mut NeuronState, potential: Scalar) {
+        if state.refractory <= 0.0 {
+            state.potential += potential;
+        }
+    }
+
+    fn process(&self, state: &mut NeuronState, delta_time: Scalar) -> Option<Scalar> {
+        if state.refractory > 0.0 {
+            state.refractory = (state.refractory - delta_time).max(0.0);
+            state.potential = self.reset_potential;
+            return None;
+        }
+        let potential = state.potential;
+        if potential >= self.action_potential_treshold {
+            state.potential = self.reset_potential;
+            state.refractory = self.refractory_period;
+            Some(potential)
+        } else {
+            let decay = if self.time_constant > 0.0 {
+                (-delta_time / self.time_constant).exp()
+            } else {
+                0.0
+            };
+            state.potential = self.reset_potential + (potential - self.reset_potential) * decay;
+            None
+        }
+    }
+}
+
+/// Izhikevich spiking neuron model.
+/// Potential and recovery are stored relative to reset values (`c` and `b * c`),
+/// so that default neuron state is the resting state.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[repr(C)]
+pub struct IzhikevichModel {
+    pub a: Scalar,
+    pub b: Scalar,
+    pub c: Scalar,
+    pub d: Scalar,
+    /// Membrane potential (mV) at which neuron fires.
+    pub peak_potential: Scalar,
+    /// Milliseconds of model time per unit of simulation time.
+    pub time_scale: Scalar,
+    /// Longest integration step in milliseconds.
+    pub integration_step: Scalar,
+    /// Membrane potential (mV) added per unit of received potential.
+    pub input_scale: Scalar,
+    /// Potential sent to outgoing synapses on fire.
+    pub spike_potential: Scalar,
+}
+
+impl Default for IzhikevichModel {
+    fn default() -> Self {
+        Self {
+            a: 0.02,
+            b: 0.2,
+            c: -65.0,
+            d: 8.0,
+            peak_potential: 30.0,
+            time_scale: 1000.0,
+            integration_step: 0.5,
+            input_scale: 20.0,
+            spike_potential: 1.0,
+        }
+    }
+}
+
+impl IzhikevichModel {
+    pub fn merge(&self, other: &Self) -> Self {
+        Self {
+            a: merge_scalar(self.a, other.a),
+            b: merge_scalar(self.b, other.b),
+            c: merge_scalar(self.c, other.c),
+            d: merge_scalar(self.d, other.d),
+            peak_potential: merge_scalar(self.peak_potential, other.peak_potential),
+            time_scale: merge_scalar(self.time_scale, other.time_scale),
+            integration_step: merge_scalar(self.integration_step, other.integration_step),
+            input_scale: merge_scalar(self.input_scale, other.input_scale),
+            spike_potential: merge_scalar(self.spike_potential, other.spike_potential),
+        }
+    }
+
+    pub fn mutate<R>(&self, mutation: &IzhikevichMutation, rng: &mut R) -> Self
+    where
+        R: Rng,
+    {
+        Self {
+            a: mutate_scalar(&mutation.a, self.a, rng),
+            b: mutate_scalar(&mutation.b, self.b, rng),
+            c: mutate_scalar(&mutation.c, self.c, rng),
+            d: mutate_scalar(&mutation.d, self.d, rng),
+            peak_potential: mutate_scalar(&mutation.peak_potential, self.peak_potential, rng),
+            time_scale: mutate_scalar(&mutation.time_scale, self.time_scale, rng),
+            integration_step: mutate_scalar(&mutation.integration_step, self.integration_step, rng),
+            input_scale: mutate_scalar(&mutation.input_scale, self.input_scale, rng),
+            spike_potential: mutate_scalar(&mutation.spike_potential, self.spike_potential, rng),
+        }
+    }
+}
+
+/// Per-field mutation of Izhikevich model.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct IzhikevichMutation {
+    pub a: Option<ScalarMutation>,
+    pub b: Option<ScalarMutation>,
+    pub c: Option<ScalarMutation>,
+    pub d: Option<ScalarMutation>,
+    pub peak_potential: Option<ScalarMutation>,
+    pub time_scale: Option<ScalarMutation>,
+    pub integration_step: Option<ScalarMutation>,
+    pub input_scale: Option<ScalarMutation>,
+    pub spike_potential: Option<ScalarMutation>,
+}
+
+impl NeuronModel for IzhikevichModel {
+    fn receive(&self, state: &mut NeuronState, potential: Scalar) {
+        state.potential += potential * self.input_scale;
+    }
+
+    fn process(&self, state: &mut NeuronState, delta_time: Scalar) -> Option<Scalar> {
+        let mut v = state.potential + self.c;
+        let mut u = state.recovery + self.b * self.c;
+        let time = delta_time * self.time_scale;
+        let steps = (time / self.integration_step.max(1.0e-3)).ceil().max(1.0);
+        let h = time / steps;
+        let mut fired = None;
+        for _ in 0..steps as usize {
+            if v >= self.peak_potential {
+                v = self.c;
+                u += self.d;
+                fired = Some(self.spike_potential);
+                break;
+            }
+            let dv = 0.04 * v * v + 5.0 * v + 140.0 - u;
+            let du = self.a * (self.b * v - u);
+            v = (v + h * dv).min(self.peak_potential);
+            u += h * du;
+        }
+        state.potential = v - self.c;
+        state.recovery = u - self.b * self.c;
+        fired
+    }
+}
+
+/// Set of all neuron models configured for brain.
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct NeuronModels {
+    pub default: NeuronModelType,
+    pub threshold: ThresholdModel,
+    pub leaky_integrate_and_fire: LeakyIntegrateAndFireModel,
+    pub izhikevich: IzhikevichModel,
+}
+
+impl NeuronModels {
+    #[inline]
+    pub fn get(&self, model: Option<NeuronModelType>) -> &dyn NeuronModel {
+        match model.unwrap_or(self.default) {
+            NeuronModelType::Threshold => &self.threshold,
+            NeuronModelType::LeakyIntegrateAndFire => &self.leaky_integrate_and_fire,
+            NeuronModelType::Izhikevich => &self.izhikevich,
+        }
+    }
+}
@@ -1,5 +1,8 @@
 use crate::brain::Brain;
+use crate::config::ConfigMutation;
+use crate::graph::connect_unreachable_effectors;
 use crate::neuron::{NeuronID, Position};
+use crate::rng::BrainRng;
 use crate::Scalar;
 use rand::{thread_rng, Rng};
 use serde::{Deserialize, Serialize};
@@ -16,6 +19,36 @@ pub struct OffspringBuilder {
     new_effectors: usize,
     no_loop_connections: bool,
     max_connecting_tries: usize,
+    #[serde(default)]
+    inhibitory_neurons: Scalar,
+    #[serde(default)]
+    reachable_effectors: bool,
+    #[serde(default)]
+    seed: Option<u64>,
+    #[serde(default)]
+    homology_range: Option<Scalar>,
+    #[serde(default)]
+    config_mutation: ConfigMutation,
+    #[serde(default)]
+    kill_neurons: usize,
+    #[serde(default)]
+    kill_connections: usize,
+    #[serde(default)]
+    reroute_connections: usize,
+    #[serde(default)]
+    kill_sensors: usize,
+    #[serde(default)]
+    kill_effectors: usize,
+    #[serde(default)]
+    min_sensors: usize,
+    #[serde(default)]
+    min_effectors: usize,
+    #[serde(default)]
+    move_neurons: usize,
+    #[serde(default)]
+    move_range: Scalar,
+    #[serde(default)]
+    receptors_jitter: Scalar,
 }
 
 impl Default for OffspringBuilder {
@@ -30,6 +63,21 @@ impl Default for OffspringBuilder {
             new_effectors: 1,
             no_loop_connections: true,
             max_connecting_tries: 10,
+            inhibitory_neurons: 0.0,
+            reachable_effectors: false,
+            seed: None,
+            homology_range: None,
+            config_mutation: Default::default(),
+            kill_neurons: 0,
+            kill_connections: 0,
+            reroute_connections: 0,
+            kill_sensors: 0,
+            kill_effectors: 0,
+            min_sensors: 0,
+            min_effectors: 0,
+            move_neurons: 0,
+            move_range: 0.0,
+            receptors_jitter: 0.0,
         }
     }
 }
@@ -84,44 +132,159 @@ impl OffspringBuilder {
         self
     }
 
-    pub fn build_mutated(mut self, source: &Brain) -> Brain {
-        let mut brain = source.duplicate();
-        let mut rng = thread_rng();
+    /// Sets probability (0..1) of new neuron being inhibitory.
+    pub fn inhibitory_neurons(mut self, value: Scalar) -> Self {
+        self.inhibitory_neurons = value;
+        self
+    }
+
+    /// Connects effectors that no sensor can reach with neurons that receive sensor input.
+    /// It is best effort: effectors stay unreachable when no such neuron can be bound to them
+    /// (e.g. when brain has no sensors).
+    pub fn reachable_effectors(mut self, value: bool) -> Self {
+        self.reachable_effectors = value;
+        self
+    }
+
+    /// Makes building deterministic by seeding random number generator with given value.
+    pub fn seed(mut self, value: u64) -> Self {
+        self.seed = Some(value);
+        self
+    }
+
+    /// Makes merging cross over homologous neurons of parents within given range (see
+    /// `Brain::crossover`) instead of randomly killing neurons of their union.
+    /// NOTE: First parent keeps its structure, so it should be the fitter one.
+    pub fn homology_range(mut self, value: Option<Scalar>) -> Self {
+        self.homology_range = value;
+        self
+    }
+
+    /// Sets mutation applied to config of every offspring, so brains can evolve their dynamics.
+    pub fn config_mutation(mut self, value: ConfigMutation) -> Self {
+        self.config_mutation = value;
+        self
+    }
+
+    /// Sets number of random neurons to kill, together with their synapses.
+    /// NOTE: Neurons bound to sensors or effectors are never killed this way.
+    pub fn kill_neurons(mut self, value: usize) -> Self {
+        self.kill_neurons = value;
+        self
+    }
+
+    /// Sets number of random synapses to remove.
+    pub fn kill_connections(mut self, value: usize) -> Self {
+        self.kill_connections = value;
+        self
+    }
+
+    /// Sets number of random synapses to reconnect to other neighbor of their source neuron.
+    pub fn reroute_connections(mut self, value: usize) -> Self {
+        self.reroute_connections = value;
+        self
+    }
+
+    /// Sets number of random sensors to remove (down to `min_sensors`).
+    pub fn kill_sensors(mut self, value: usize) -> Self {
+        self.kill_sensors = value;
+        self
+    }
+
+    /// Sets number of random effectors to remove (down to `min_effectors`).
+    pub fn kill_effectors(mut self, value: usize) -> Self {
+        self.kill_effectors = value;
+        self
+    }
+
+    pub fn min_sensors(mut self, value: usize) -> Self {
+        self.min_sensors = value;
+        self
+    }
+
+    pub fn min_effectors(mut self, value: usize) -> Self {
+        self.min_effectors = value;
+        self
+    }
+
+    /// Sets number of random neurons to move by up to `move_range` distance.
+    pub fn move_neurons(mut self, value: usize) -> Self {
+        self.move_neurons = value;
+        self
+    }
+
+    pub fn move_range(mut self, value: Scalar) -> Self {
+        self.move_range = value;
+        self
+    }
+
+    /// Sets max change of receptors of every synapse (receptors never get negative).
+    pub fn receptors_jitter(mut self, value: Scalar) -> Self {
+        self.receptors_jitter = value;
+        self
+    }
+
+    pub fn build_mutated(self, source: &Brain) -> Brain {
+        match self.seed {
+            Some(seed) => self.build_mutated_with_rng(source, &mut BrainRng::from_u64(seed)),
+            None => self.build_mutated_with_rng(source, &mut thread_rng()),
+        }
+    }
+
+    pub fn build_mutated_with_rng<R>(mut self, source: &Brain, rng: &mut R) -> Brain
+    where
+        R: Rng,
+    {
+        let mut brain = source.duplicate_with_rng(rng);
+        brain.set_config(brain.config().mutate(&self.config_mutation, rng));
+        self.remove_and_rewire(&mut brain, rng);
 
         let mut neurons = brain.get_neurons();
-        for _ in 0..self.new_neurons {
-            neurons.push(self.make_neighbor_neuron(&neurons, &mut brain, &mut rng));
+        if !neurons.is_empty() {
+            for _ in 0..self.new_neurons {
+                neurons.push(self.make_neighbor_neuron(&neurons, &mut brain, rng));
+            }
         }
 
         let neuron_positions = neurons
             .iter()
             .map(|id| (*id, brain.neuron(*id).unwrap().position()))
             .collect::<Vec<_>>();
-        for _ in 0..self.new_sensors {
-            let mut tries = self.max_connecting_tries + 1;
-            while tries > 0 && !self.make_peripheral_sensor(&neuron_positions, &mut brain, &mut rng)
-            {
-                tries -= 1;
+        // removal mutations could leave no neurons to grow new structure from.
+        if !neuron_positions.is_empty() {
+            for _ in 0..self.new_sensors {
+                let mut tries = self.max_connecting_tries + 1;
+                while tries > 0 && !self.make_peripheral_sensor(&neuron_positions, &mut brain, rng)
+                {
+                    tries -= 1;
+                }
             }
-        }
-        for _ in 0..self.new_effectors {
-            let mut tries = self.max_connecting_tries + 1;
-            while tries > 0
-                && !self.make_peripheral_effector(&neuron_positions, &mut brain, &mut rng)
-            {
-                tries -= 1;
+            for _ in 0..self.new_effectors {
+                let mut tries = self.max_connecting_tries + 1;
+                while tries > 0
+                    && !self.make_peripheral_effector(&neuron_positions, &mut brain, rng)
+                {
+                    tries -= 1;
+                }
             }
-        }
-        for _ in 0..self.new_connections {
-            let mut tries = self.max_connecting_tries + 1;
-            while tries > 0
-                && self.connect_neighbor_neurons(&neuron_positions, &mut brain, &mut rng)
-            {
-                tries -= 1;
+            for _ in 0..self.new_connections {
+                let mut tries = self.max_connecting_tries + 1;
+                while tries > 0 && self.connect_neighbor_neurons(&neuron_positions, &mut brain, rng)
+                {
+                    tries -= 1;
+                }
             }
         }
+        if self.reachable_effectors {
+            connect_unreachable_effectors(&mut brain);
+        }
         for id in brain.get_neurons() {
-            if !brain.does_neuron_has_connections(id) {
+            // keep sensors and effectors minimum even if they are disconnected.
+            let keep = (brain.index().neuron_sensor(id).is_some()
+                && brain.get_sensors().len() <= self.min_sensors)
+                || (brain.index().neuron_effector(id).is_some()
+                    && brain.get_effectors().len() <= self.min_effectors);
+            if !keep && !brain.does_neuron_has_connections(id) {
                 drop(brain.kill_neuron(id));
             }
         }
@@ -129,48 +292,174 @@ impl OffspringBuilder {
         brain
     }
 
-    pub fn build_merged(mut self, source_a: &Brain, source_b: &Brain) -> Brain {
-        let mut brain = source_a.merge(source_b);
-        let mut rng = thread_rng();
+    pub fn build_merged(self, source_a: &Brain, source_b: &Brain) -> Brain {
+        match self.seed {
+            Some(seed) => {
+                self.build_merged_with_rng(source_a, source_b, &mut BrainRng::from_u64(seed))
+            }
+            None => self.build_merged_with_rng(source_a, source_b, &mut thread_rng()),
+        }
+    }
+
+    pub fn build_merged_with_rng<R>(
+        mut self,
+        source_a: &Brain,
+        source_b: &Brain,
+        rng: &mut R,
+    ) -> Brain
+    where
+        R: Rng,
+    {
+        let mut brain = match self.homology_range {
+            Some(range) => source_a.crossover_with_rng(source_b, range, rng),
+            None => source_a.merge_with_rng(source_b, rng),
+        };
+        brain.set_config(brain.config().mutate(&self.config_mutation, rng));
+        self.remove_and_rewire(&mut brain, rng);
 
         let mut neurons = brain.get_neurons();
-        for _ in 0..self.new_neurons {
-            neurons.push(self.make_neighbor_neuron(&neurons, &mut brain, &mut rng));
+        if !neurons.is_empty() {
+            for _ in 0..self.new_neurons {
+                neurons.push(self.make_neighbor_neuron(&neurons, &mut brain, rng));
+            }
         }
 
-        self.new_sensors += (source_a.get_sensors().len() + source_b.get_sensors().len()) / 2
-            - brain.get_sensors().len();
-        self.new_effectors += (source_a.get_effectors().len() + source_b.get_effectors().len()) / 2
-            - brain.get_effectors().len();
+        self.new_sensors += ((source_a.get_sensors().len() + source_b.get_sensors().len()) / 2)
+            .saturating_sub(brain.get_sensors().len());
+        self.new_effectors += ((source_a.get_effectors().len() + source_b.get_effectors().len())
+            / 2)
+        .saturating_sub(brain.get_effectors().len());
         let neuron_positions = neurons
             .iter()
             .map(|id| (*id, brain.neuron(*id).unwrap().position()))
             .collect::<Vec<_>>();
-        for _ in 0..self.new_sensors {
-            let mut tries = self.max_connecting_tries + 1;
-            while tries > 0 && !self.make_peripheral_sensor(&neuron_positions, &mut brain, &mut rng)
-            {
-                tries -= 1;
+        // removal mutations could leave no neurons to grow new structure from.
+        if !neuron_positions.is_empty() {
+            for _ in 0..self.new_sensors {
+                let mut tries = self.max_connecting_tries + 1;
+                while tries > 0 && !self.make_peripheral_sensor(&neuron_positions, &mut brain, rng)
+                {
+                    tries -= 1;
+                }
+            }
+            for _ in 0..self.new_effectors {
+                let mut tries = self.max_connecting_tries + 1;
+                while tries > 0
+                    && !self.make_peripheral_effector(&neuron_positions, &mut brain, rng)
+                {
+                    tries -= 1;
+                }
             }
+            for _ in 0..self.new_connections {
+                let mut tries = self.max_connecting_tries + 1;
+                while tries > 0
+                    && !self.connect_neighbor_neurons(&neuron_positions, &mut brain, rng)
+                {
+                    tries -= 1;
+                }
+            }
+        }
+        if self.reachable_effectors {
+            connect_unreachable_effectors(&mut brain);
         }
-        for _ in 0..self.new_effectors {
-            let mut tries = self.max_connecting_tries + 1;
-            while tries > 0
-                && !self.make_peripheral_effector(&neuron_positions, &mut brain, &mut rng)
-            {
-                tries -= 1;
+
+        brain
+    }
+
+    // removal mutations go before growth, so new structure is not removed right away.
+    fn remove_and_rewire<R>(&self, brain: &mut Brain, rng: &mut R)
+    where
+        R: Rng,
+    {
+        for _ in 0..self.kill_sensors {
+            let sensors = brain.get_sensors();
+            if sensors.len() <= self.min_sensors {
+                break;
             }
+            drop(brain.kill_sensor(sensors[rng.gen_range(0, sensors.len())]));
         }
-        for _ in 0..self.new_connections {
-            let mut tries = self.max_connecting_tries + 1;
-            while tries > 0
-                && !self.connect_neighbor_neurons(&neuron_positions, &mut brain, &mut rng)
-            {
-                tries -= 1;
+        for _ in 0..self.kill_effectors {
+            let effectors = brain.get_effectors();
+            if effectors.len() <= self.min_effectors {
+                break;
             }
+            drop(brain.kill_effector(effectors[rng.gen_range(0, effectors.len())]));
         }
+        for _ in 0..self.kill_neurons {
+            let neurons = brain
+                .get_neurons()
+                .into_iter()
+                .filter(|id| {
+                    brain.index().neuron_sensor(*id).is_none()
+                        && brain.index().neuron_effector(*id).is_none()
+                })
+                .collect::<Vec<_>>();
+            if neurons.is_empty() {
+                break;
+            }
+            drop(brain.kill_neuron(neurons[rng.gen_range(0, neurons.len())]));
+        }
+        for _ in 0..self.kill_connections {
+            if brain.synapses().is_empty() {
+                break;
+            }
+            let synapse = &brain.synapses()[rng.gen_range(0, brain.synapses().len())];
+            let (from, to) = (synapse.source(), synapse.target());
+            drop(brain.unbind_neurons(from, to));
+        }
+        for _ in 0..self.reroute_connections {
+            if brain.synapses().is_empty() {
+                break;
+            }
+            self.reroute_connection(brain, rng);
+        }
+        let neurons = brain.get_neurons();
+        if !neurons.is_empty() && self.move_range > 0.0 {
+            for _ in 0..self.move_neurons {
+                let id = neurons[rng.gen_range(0, neurons.len())];
+                let position = brain.neuron(id).unwrap().position();
+                let distance = rng.gen_range(0.0, self.move_range);
+                let position = self.make_new_position(position, distance, rng);
+                drop(brain.set_neuron_position(id, position));
+            }
+        }
+        if self.receptors_jitter > 0.0 {
+            for synapse in brain.synapses_mut() {
+                let change = rng.gen_range(-self.receptors_jitter, self.receptors_jitter);
+                synapse.receptors = (synapse.receptors + change).max(0.0);
+            }
+        }
+    }
 
-        brain
+    // moves synapse target to other neuron in neurogenesis range of its source, keeping receptors.
+    fn reroute_connection<R>(&self, brain: &mut Brain, rng: &mut R)
+    where
+        R: Rng,
+    {
+        let synapse = &brain.synapses()[rng.gen_range(0, brain.synapses().len())];
+        let (from, to, receptors) = (synapse.source(), synapse.target(), synapse.receptors());
+        let origin = brain.neuron(from).unwrap().position();
+        let targets = brain
+            .neurons()
+            .iter()
+            .filter(|n| {
+                n.id() != from
+                    && n.id() != to
+                    && n.position().distance(origin) <= self.max_neurogenesis_range
+                    && !brain.are_neurons_connected(from, n.id())
+                    && (!self.no_loop_connections || !brain.are_neurons_connected(n.id(), from))
+            })
+            .map(|n| n.id())
+            .collect::<Vec<_>>();
+        if targets.is_empty() {
+            return;
+        }
+        let target = targets[rng.gen_range(0, targets.len())];
+        if let Ok(Some(_)) = brain.bind_neurons(from, target) {
+            drop(brain.unbind_neurons(from, to));
+            let index = brain.index().synapse(from, target).unwrap();
+            brain.synapses_mut()[index].receptors = receptors;
+        }
     }
 
     fn make_peripheral_sensor<R>(
@@ -226,7 +515,11 @@ impl OffspringBuilder {
         let origin = neurons[rng.gen_range(0, neurons.len()) % neurons.len()];
         let origin_pos = brain.neuron(origin).unwrap().position();
         let new_position = self.make_new_position(origin_pos, distance, rng);
-        brain.create_neuron(new_position)
+        let id = brain.create_neuron(new_position);
+        if self.inhibitory_neurons > 0.0 && rng.gen::<Scalar>() < self.inhibitory_neurons {
+            drop(brain.set_neuron_inhibitory(id, true));
+        }
+        id
     }
 
     fn connect_neighbor_neurons<R>(
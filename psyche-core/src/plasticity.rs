@@ -0,0 +1,166 @@
+use crate::config::{merge_scalar, mutate_scalar, ScalarMutation};
+use crate::Scalar;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+/// Describes how synapse receptors change in response to neural activity.
+pub trait PlasticityRule {
+    /// Receptors change applied for every impulse that reaches synapse target.
+    fn impulse_arrived(&self, _delta_time: Scalar) -> Scalar {
+        0.0
+    }
+
+    /// Receptors change applied when synapse source neuron fires.
+    /// `since_post_spike` is time passed since synapse target neuron fired last time.
+    fn pre_spike(&self, _since_post_spike: Option<Scalar>) -> Scalar {
+        0.0
+    }
+
+    /// Receptors change applied when synapse target neuron fires.
+    /// `since_pre_spike` is time passed since synapse source neuron fired last time.
+    fn post_spike(&self, _since_pre_spike: Option<Scalar>) -> Scalar {
+        0.0
+    }
+}
+
+/// Selects plasticity rule used to update synapses receptors.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[repr(C)]
+pub enum PlasticityRuleType {
+    #[default]
+    Receptors,
+    Stdp,
+}
+
+/// Receptors grow with every impulse that passes through synapse.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[repr(C)]
+pub struct ReceptorsRule {
+    pub receptors_excitation: Scalar,
+}
+
+impl Default for ReceptorsRule {
+    fn default() -> Self {
+        Self {
+            receptors_excitation: 1.0,
+        }
+    }
+}
+
+impl PlasticityRule for ReceptorsRule {
+    fn impulse_arrived(&self, delta_time: Scalar) -> Scalar {
+        self.receptors_excitation * delta_time
+    }
+}
+
+/// Spike-timing-dependent plasticity.
+/// Synapse is potentiated when its source fires shortly before its target,
+/// and depressed when its source fires shortly after its target.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[repr(C)]
+pub struct StdpRule {
+    /// Largest receptors gain (pre before post).
+    pub potentiation: Scalar,
+    /// Largest receptors loss (post before pre).
+    pub depression: Scalar,
+    /// Time constant of potentiation window.
+    pub potentiation_time: Scalar,
+    /// Time constant of depression window.
+    pub depression_time: Scalar,
+    /// Spikes further apart than this are not correlated.
+    pub window: Scalar,
+}
+
+impl Default for StdpRule {
+    fn default() -> Self {
+        Self {
+            potentiation: 0.1,
+            depression: 0.12,
+            potentiation_time: 0.02,
+            depression_time: 0.02,
+            window: 0.1,
+        }
+    }
+}
+
+impl StdpRule {
+    pub fn merge(&self, other: &Self) -> Self {
+        Self {
+            potentiation: merge_scalar(self.potentiation, other.potentiation),
+            depression: merge_scalar(self.depression, other.depression),
+            potentiation_time: merge_scalar(self.potentiation_time, other.potentiation_time),
+            depression_time: merge_scalar(self.depression_time, other.depression_time),
+            window: merge_scalar(self.window, other.window),
+        }
+    }
+
+    pub fn mutate<R>(&self, mutation: &StdpMutation, rng: &mut R) -> Self
+    where
+        R: Rng,
+    {
+        Self {
+            potentiation: mutate_scalar(&mutation.potentiation, self.potentiation, rng),
+            depression: mutate_scalar(&mutation.depression, self.depression, rng),
+            potentiation_time: mutate_scalar(
+                &mutation.potentiation_time,
+                self.potentiation_time,
+                rng,
+            ),
+            depression_time: mutate_scalar(&mutation.depression_time, self.depression_time, rng),
+            window: mutate_scalar(&mutation.window, self.window, rng),
+        }
+    }
+}
+
+/// Per-field mutation of spike-timing-dependent plasticity.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StdpMutation {
+    pub potentiation: Option<ScalarMutation>,
+    pub depression: Option<ScalarMutation>,
+    pub potentiation_time: Option<ScalarMutation>,
+    pub depression_time: Option<ScalarMutation>,
+    pub window: Option<ScalarMutation>,
+}
+
+impl PlasticityRule for StdpRule {
+    fn pre_spike(&self, since_post_spike: Option<Scalar>) -> Scalar {
+        match since_post_spike {
+            Some(t) if t <= self.window => -self.depression * decay(t, self.depression_time),
+            _ => 0.0,
+        }
+    }
+
+    fn post_spike(&self, since_pre_spike: Option<Scalar>) -> Scalar {
+        match since_pre_spike {
+            Some(t) if t <= self.window => self.potentiation * decay(t, self.potentiation_time),
+            _ => 0.0,
+        }
+    }
+}
+
+/// Set of all plasticity rules configured for brain.
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct PlasticityRules {
+    pub rule: PlasticityRuleType,
+    pub receptors: ReceptorsRule,
+    pub stdp: StdpRule,
+}
+
+impl PlasticityRules {
+    #[inline]
+    pub fn get(&self) -> &dyn PlasticityRule {
+        match self.rule {
+            PlasticityRuleType::Receptors => &self.receptors,
+            PlasticityRuleType::Stdp => &self.stdp,
+        }
+    }
+}
+
+fn decay(time: Scalar, time_constant: Scalar) -> Scalar {
+    if time_constant > 0.0 {
+        (-time / time_constant).exp()
+    } else {
+        0.0
+    }
+}
@@ -0,0 +1,253 @@
+use crate::brain::{Brain, BrainActivityStats};
+use crate::effector::EffectorID;
+use crate::error::*;
+use crate::neuron::NeuronID;
+use crate::Scalar;
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+
+/// Names of brain activity stats columns, in order of values stored per frame.
+pub const STATS_COLUMNS: &[&str] = &[
+    "neurons_count",
+    "synapses_count",
+    "impulses_count",
+    "neurons_potential",
+    "neurons_potential_min",
+    "neurons_potential_max",
+    "impulses_potential",
+    "impulses_potential_min",
+    "impulses_potential_max",
+    "all_potential",
+    "all_potential_min",
+    "all_potential_max",
+    "incoming_neuron_connections_min",
+    "incoming_neuron_connections_max",
+    "outgoing_neuron_connections_min",
+    "outgoing_neuron_connections_max",
+    "synapses_receptors_min",
+    "synapses_receptors_max",
+    "mean_firing_rate",
+    "active_neurons",
+    "strongly_connected_components",
+    "sensor_effector_path_length",
+    "reachable_effectors",
+];
+
+/// Captures brain activity frame by frame into columnar buffers.
+/// Values of neurons and effectors that do not exist anymore are recorded as NaN.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Recorder {
+    neurons: Vec<NeuronID>,
+    effectors: Vec<EffectorID>,
+    stats: bool,
+    times: Vec<Scalar>,
+    // (frame, neuron)
+    spikes: Vec<(usize, NeuronID)>,
+    // frames x neurons
+    potentials: Vec<Scalar>,
+    // frames x effectors
+    outputs: Vec<Scalar>,
+    // frames x stats columns
+    stats_values: Vec<Scalar>,
+}
+
+impl Recorder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets neurons which potential is recorded.
+    /// Changes columns layout, so already recorded frames are cleared.
+    pub fn neurons(mut self, value: Vec<NeuronID>) -> Self {
+        self.neurons = value;
+        self.clear();
+        self
+    }
+
+    /// Sets effectors which output is recorded.
+    /// Changes columns layout, so already recorded frames are cleared.
+    pub fn effectors(mut self, value: Vec<EffectorID>) -> Self {
+        self.effectors = value;
+        self.clear();
+        self
+    }
+
+    /// Enables recording of brain activity stats.
+    /// Changes columns layout, so already recorded frames are cleared.
+    pub fn stats(mut self, value: bool) -> Self {
+        self.stats = value;
+        self.clear();
+        self
+    }
+
+    #[inline]
+    pub fn frames_count(&self) -> usize {
+        self.times.len()
+    }
+
+    #[inline]
+    pub fn times(&self) -> &[Scalar] {
+        &self.times
+    }
+
+    /// List of (frame, neuron) pairs for every recorded neuron fire.
+    #[inline]
+    pub fn spikes(&self) -> &[(usize, NeuronID)] {
+        &self.spikes
+    }
+
+    pub fn neuron_potentials(&self, id: NeuronID) -> Option<Vec<Scalar>> {
+        let column = self.neurons.iter().position(|n| *n == id)?;
+        Some(column_values(&self.potentials, self.neurons.len(), column))
+    }
+
+    pub fn effector_outputs(&self, id: EffectorID) -> Option<Vec<Scalar>> {
+        let column = self.effectors.iter().position(|e| *e == id)?;
+        Some(column_values(&self.outputs, self.effectors.len(), column))
+    }
+
+    pub fn stats_values(&self, name: &str) -> Option<Vec<Scalar>> {
+        if !self.stats {
+            return None;
+        }
+        let column = STATS_COLUMNS.iter().position(|c| *c == name)?;
+        Some(column_values(
+            &self.stats_values,
+            STATS_COLUMNS.len(),
+            column,
+        ))
+    }
+
+    /// Captures current state of brain as next frame. Call it after `Brain::process`.
+    pub fn record(&mut self, brain: &Brain) {
+        let frame = self.times.len();
+        let time = brain.time();
+        self.times.push(time);
+        for id in brain.get_neurons() {
+            if brain.neuron(id).unwrap().last_spike() == Some(time) {
+                self.spikes.push((frame, id));
+            }
+        }
+        for id in &self.neurons {
+            self.potentials.push(
+                brain
+                    .neuron(*id)
+                    .map_or(Scalar::NAN, |neuron| neuron.potential()),
+            );
+        }
+        for id in &self.effectors {
+            self.outputs
+                .push(brain.effector_potential(*id).unwrap_or(Scalar::NAN));
+        }
+        if self.stats {
+            self.stats_values
+                .extend(stats_row(&brain.build_activity_stats()));
+        }
+    }
+
+    /// Removes all recorded frames, keeping columns layout.
+    pub fn clear(&mut self) {
+        self.times.clear();
+        self.spikes.clear();
+        self.potentials.clear();
+        self.outputs.clear();
+        self.stats_values.clear();
+    }
+
+    /// Writes time series (one row per frame) as CSV.
+    pub fn write_csv<W>(&self, mut writer: W) -> Result<()>
+    where
+        W: Write,
+    {
+        write!(writer, "time,spikes")?;
+        for id in &self.neurons {
+            write!(writer, ",potential:{}", id.uuid())?;
+        }
+        for id in &self.effectors {
+            write!(writer, ",effector:{}", id.uuid())?;
+        }
+        if self.stats {
+            for name in STATS_COLUMNS {
+                write!(writer, ",{}", name)?;
+            }
+        }
+        writeln!(writer)?;
+        let mut spikes = vec![0; self.times.len()];
+        for (frame, _) in &self.spikes {
+            spikes[*frame] += 1;
+        }
+        for (frame, time) in self.times.iter().enumerate() {
+            write!(writer, "{},{}", time, spikes[frame])?;
+            write_csv_values(&mut writer, &self.potentials, self.neurons.len(), frame)?;
+            write_csv_values(&mut writer, &self.outputs, self.effectors.len(), frame)?;
+            if self.stats {
+                write_csv_values(&mut writer, &self.stats_values, STATS_COLUMNS.len(), frame)?;
+            }
+            writeln!(writer)?;
+        }
+        Ok(())
+    }
+
+    /// Writes spike raster (one row per neuron fire) as CSV.
+    pub fn write_spikes_csv<W>(&self, mut writer: W) -> Result<()>
+    where
+        W: Write,
+    {
+        writeln!(writer, "time,neuron")?;
+        for (frame, id) in &self.spikes {
+            writeln!(writer, "{},{}", self.times[*frame], id.uuid())?;
+        }
+        Ok(())
+    }
+}
+
+fn stats_row(stats: &BrainActivityStats) -> Vec<Scalar> {
+    vec![
+        stats.neurons_count as Scalar,
+        stats.synapses_count as Scalar,
+        stats.impulses_count as Scalar,
+        stats.neurons_potential.0,
+        stats.neurons_potential.1.start,
+        stats.neurons_potential.1.end,
+        stats.impulses_potential.0,
+        stats.impulses_potential.1.start,
+        stats.impulses_potential.1.end,
+        stats.all_potential.0,
+        stats.all_potential.1.start,
+        stats.all_potential.1.end,
+        stats.incoming_neuron_connections.start as Scalar,
+        stats.incoming_neuron_connections.end as Scalar,
+        stats.outgoing_neuron_connections.start as Scalar,
+        stats.outgoing_neuron_connections.end as Scalar,
+        stats.synapses_receptors.start,
+        stats.synapses_receptors.end,
+        stats.mean_firing_rate,
+        stats.active_neurons,
+        stats.strongly_connected_components as Scalar,
+        stats.sensor_effector_path_length,
+        stats.reachable_effectors as Scalar,
+    ]
+}
+
+fn column_values(values: &[Scalar], columns: usize, column: usize) -> Vec<Scalar> {
+    values
+        .iter()
+        .skip(column)
+        .step_by(columns)
+        .cloned()
+        .collect()
+}
+
+fn write_csv_values<W>(writer: &mut W, values: &[Scalar], columns: usize, row: usize) -> Result<()>
+where
+    W: Write,
+{
+    for value in &values[(row * columns)..((row + 1) * columns)] {
+        if value.is_nan() {
+            write!(writer, ",")?;
+        } else {
+            write!(writer, ",{}", value)?;
+        }
+    }
+    Ok(())
+}
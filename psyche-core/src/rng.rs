@@ -0,0 +1,109 @@
+use rand::{thread_rng, Error as RandError, Rng, RngCore, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+/// Serializable Xorshift128 random number generator.
+/// Every brain carries its own generator so that snapshots continue with the
+/// exact same random sequence after being loaded.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct BrainRng {
+    state: [u32; 4],
+}
+
+impl Default for BrainRng {
+    #[inline]
+    fn default() -> Self {
+        Self::from_entropy()
+    }
+}
+
+impl BrainRng {
+    /// Creates generator seeded from thread local entropy source.
+    #[inline]
+    pub fn from_entropy() -> Self {
+        Self::from_rng_source(&mut thread_rng())
+    }
+
+    /// Creates generator with state expanded from 64-bit seed.
+    pub fn from_u64(seed: u64) -> Self {
+        let mut seed = seed;
+        let mut state = [0; 4];
+        for pair in state.chunks_mut(2) {
+            let value = splitmix64(&mut seed);
+            pair[0] = value as u32;
+            pair[1] = (value >> 32) as u32;
+        }
+        Self::from_state(state)
+    }
+
+    /// Creates generator seeded from another random number generator.
+    /// Seed is scrambled so that generator never shares its sequence with the source.
+    #[inline]
+    pub fn from_rng_source<R>(rng: &mut R) -> Self
+    where
+        R: Rng + ?Sized,
+    {
+        Self::from_u64(rng.gen())
+    }
+
+    fn from_state(state: [u32; 4]) -> Self {
+        // xorshift cannot leave all-zeros state.
+        if state.iter().all(|v| *v == 0) {
+            Self {
+                state: [0x193a_6754, 0xa8a7_d469, 0x9783_0e05, 0x113b_a7bb],
+            }
+        } else {
+            Self { state }
+        }
+    }
+}
+
+impl RngCore for BrainRng {
+    #[inline]
+    fn next_u32(&mut self) -> u32 {
+        let [x, y, z, w] = self.state;
+        let t = x ^ (x << 11);
+        let result = w ^ (w >> 19) ^ (t ^ (t >> 8));
+        self.state = [y, z, w, result];
+        result
+    }
+
+    #[inline]
+    fn next_u64(&mut self) -> u64 {
+        let low = u64::from(self.next_u32());
+        let high = u64::from(self.next_u32());
+        (high << 32) | low
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for chunk in dest.chunks_mut(4) {
+            let bytes = self.next_u32().to_le_bytes();
+            chunk.copy_from_slice(&bytes[..chunk.len()]);
+        }
+    }
+
+    #[inline]
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), RandError> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+impl SeedableRng for BrainRng {
+    type Seed = [u8; 16];
+
+    fn from_seed(seed: Self::Seed) -> Self {
+        let mut state = [0; 4];
+        for (value, bytes) in state.iter_mut().zip(seed.chunks(4)) {
+            *value = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+        }
+        Self::from_state(state)
+    }
+}
+
+fn splitmix64(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
@@ -1,11 +1,122 @@
 use crate::id::ID;
+use crate::label::Label;
 use crate::neuron::NeuronID;
+use crate::Scalar;
+use rand::Rng;
 use serde::{Deserialize, Serialize};
 
 pub type SensorID = ID<Sensor>;
 
+/// Describes how sensor value is turned into potential pushed to its target neuron.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub enum SensorMode {
+    /// Sensor is only triggered manually with `Brain::sensor_trigger_impulse`.
+    #[default]
+    Impulse,
+    /// Pushes `value` potential per second.
+    ConstantCurrent,
+    /// Regular spike train with frequency of `value * max_rate` spikes per second.
+    RateCoded {
+        max_rate: Scalar,
+        spike_potential: Scalar,
+    },
+    /// Random spikes with average frequency of `value * max_rate` spikes per second.
+    Poisson {
+        max_rate: Scalar,
+        spike_potential: Scalar,
+    },
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Sensor {
     pub(crate) id: SensorID,
     pub(crate) target: NeuronID,
+    #[serde(default)]
+    pub(crate) mode: SensorMode,
+    #[serde(default)]
+    pub(crate) value: Scalar,
+    #[serde(default)]
+    pub(crate) phase: Scalar,
+    #[serde(default)]
+    pub(crate) label: Label,
+}
+
+impl Sensor {
+    pub(crate) fn new(id: SensorID, target: NeuronID, mode: SensorMode) -> Self {
+        Self {
+            id,
+            target,
+            mode,
+            value: 0.0,
+            phase: 0.0,
+            label: Default::default(),
+        }
+    }
+
+    #[inline]
+    pub fn id(&self) -> SensorID {
+        self.id
+    }
+
+    #[inline]
+    pub fn target(&self) -> NeuronID {
+        self.target
+    }
+
+    #[inline]
+    pub fn mode(&self) -> SensorMode {
+        self.mode
+    }
+
+    #[inline]
+    pub fn label(&self) -> &Label {
+        &self.label
+    }
+
+    /// Current analog input of sensor.
+    #[inline]
+    pub fn value(&self) -> Scalar {
+        self.value
+    }
+
+    // returns potential that has to be pushed to target neuron in this step.
+    pub(crate) fn process<R>(&mut self, delta_time: Scalar, rng: &mut R) -> Option<Scalar>
+    where
+        R: Rng,
+    {
+        match self.mode {
+            SensorMode::Impulse => None,
+            SensorMode::ConstantCurrent => {
+                if self.value != 0.0 {
+                    Some(self.value * delta_time)
+                } else {
+                    None
+                }
+            }
+            SensorMode::RateCoded {
+                max_rate,
+                spike_potential,
+            } => {
+                self.phase += self.value.max(0.0) * max_rate * delta_time;
+                if self.phase >= 1.0 {
+                    let spikes = self.phase.floor();
+                    self.phase -= spikes;
+                    Some(spikes * spike_potential)
+                } else {
+                    None
+                }
+            }
+            SensorMode::Poisson {
+                max_rate,
+                spike_potential,
+            } => {
+                let probability = self.value.max(0.0) * max_rate * delta_time;
+                if probability > 0.0 && rng.gen::<Scalar>() < probability {
+                    Some(spike_potential)
+                } else {
+                    None
+                }
+            }
+        }
+    }
 }
@@ -1,9 +1,22 @@
 #![cfg(test)]
 use crate::brain::*;
 use crate::brain_builder::*;
+use crate::compatibility::*;
 use crate::config::*;
+use crate::effector::*;
+use crate::event::*;
+use crate::graph::*;
+use crate::label::*;
+use crate::lineage::*;
 use crate::neuron::*;
+use crate::neuron_model::*;
 use crate::offspring_builder::*;
+use crate::plasticity::*;
+use crate::recorder::*;
+use crate::rng::*;
+use crate::sensor::*;
+use crate::topology::*;
+use rand::Rng;
 
 #[test]
 fn test_brain() {
@@ -36,6 +49,939 @@ fn test_brain() {
     assert!(brain.effector_potential_release(e1).unwrap() > 0.0);
 }
 
+#[test]
+fn test_inhibitory_neurons() {
+    let mut brain = Brain::new();
+    let n1 = brain.create_neuron(Position {
+        x: 0.0,
+        y: 0.0,
+        z: 0.0,
+    });
+    let n2 = brain.create_neuron(Position {
+        x: 1.0,
+        y: 0.0,
+        z: 0.0,
+    });
+    let n3 = brain.create_neuron(Position {
+        x: 4.0,
+        y: 0.0,
+        z: 0.0,
+    });
+    let s1 = brain.create_sensor(n1).unwrap();
+    let e1 = brain.create_effector(n3).unwrap();
+    brain.bind_neurons(n1, n2).unwrap();
+    brain.bind_neurons(n2, n3).unwrap();
+    brain.set_neuron_inhibitory(n2, true).unwrap();
+    brain.sensor_trigger_impulse(s1, 10.0).unwrap();
+
+    for _ in 0..4 {
+        brain.process(1.0).unwrap();
+    }
+    assert!(brain.effector_potential_release(e1).unwrap() < 0.0);
+    let map = brain.build_activity_map_default();
+    assert_eq!(map.neurons.len(), 2);
+    assert_eq!(map.inhibitory_neurons.len(), 1);
+    assert_eq!(map.neurons_potential.len(), 2);
+    assert_eq!(map.inhibitory_neurons_potential.len(), 1);
+    assert_eq!(map.connections.len(), 1);
+    assert_eq!(map.inhibitory_connections.len(), 1);
+
+    let brain = BrainBuilder::new()
+        .neurons(50)
+        .connections(50)
+        .inhibitory_neurons(1.0)
+        .seed(42)
+        .build();
+    let inhibitory = brain.neurons().iter().filter(|n| n.is_inhibitory()).count();
+    assert!(inhibitory > 0);
+    assert_eq!(
+        brain.build_activity_map_default().inhibitory_neurons.len(),
+        inhibitory
+    );
+}
+
+#[test]
+fn test_stdp() {
+    let rule = StdpRule::default();
+    assert!(rule.post_spike(Some(0.01)) > rule.post_spike(Some(0.05)));
+    assert!(rule.post_spike(Some(0.01)) > 0.0);
+    assert!(rule.pre_spike(Some(0.01)) < 0.0);
+    assert_eq!(rule.post_spike(None), 0.0);
+    assert_eq!(rule.post_spike(Some(rule.window * 2.0)), 0.0);
+
+    let mut brain = Brain::new();
+    brain.set_config(Config {
+        propagation_speed: 100.0,
+        receptors_inhibition: 0.0,
+        plasticity_rule: PlasticityRuleType::Stdp,
+        ..Default::default()
+    });
+    let pa = Position {
+        x: 0.0,
+        y: 0.0,
+        z: 0.0,
+    };
+    let pb = Position {
+        x: 1.0,
+        y: 0.0,
+        z: 0.0,
+    };
+    let pc = Position {
+        x: 0.0,
+        y: 3.0,
+        z: 0.0,
+    };
+    let a = brain.create_neuron(pa);
+    let b = brain.create_neuron(pb);
+    let c = brain.create_neuron(pc);
+    brain.bind_neurons(a, b).unwrap();
+    brain.bind_neurons(a, c).unwrap();
+    brain.bind_neurons(c, b).unwrap();
+    let sensor = brain.create_sensor(a).unwrap();
+    let receptors = |brain: &Brain, from: Position, to: Position| {
+        brain
+            .build_activity_map(activity::CONNECTIONS)
+            .connections
+            .iter()
+            .find(|(f, t, _)| *f == from && *t == to)
+            .unwrap()
+            .2
+    };
+    let ab = receptors(&brain, pa, pb);
+    let cb = receptors(&brain, pc, pb);
+    brain.sensor_trigger_impulse(sensor, 10.0).unwrap();
+    for _ in 0..5 {
+        brain.process(0.01).unwrap();
+    }
+    assert!(brain.neuron(a).unwrap().last_spike().is_some());
+    assert!(brain.neuron(c).unwrap().last_spike() > brain.neuron(b).unwrap().last_spike());
+    assert!(receptors(&brain, pa, pb) > ab);
+    assert!(receptors(&brain, pc, pb) < cb);
+}
+
+#[test]
+fn test_reward() {
+    let mut brain = Brain::new();
+    brain.set_config(Config {
+        receptors_excitation: 0.0,
+        receptors_inhibition: 0.0,
+        ..Default::default()
+    });
+    let pa = Position {
+        x: 0.0,
+        y: 0.0,
+        z: 0.0,
+    };
+    let pb = Position {
+        x: 1.0,
+        y: 0.0,
+        z: 0.0,
+    };
+    let pc = Position {
+        x: 0.0,
+        y: 1.0,
+        z: 0.0,
+    };
+    let a = brain.create_neuron(pa);
+    let b = brain.create_neuron(pb);
+    let c = brain.create_neuron(pc);
+    brain.bind_neurons(a, b).unwrap();
+    brain.bind_neurons(c, b).unwrap();
+    let sensor = brain.create_sensor(a).unwrap();
+    let receptors = |brain: &Brain, from: Position| {
+        brain
+            .build_activity_map(activity::CONNECTIONS)
+            .connections
+            .iter()
+            .find(|(f, _, _)| *f == from)
+            .unwrap()
+            .2
+    };
+    let (ab, cb) = (receptors(&brain, pa), receptors(&brain, pc));
+    brain.sensor_trigger_impulse(sensor, 10.0).unwrap();
+    for _ in 0..3 {
+        brain.process(1.0).unwrap();
+    }
+    brain.apply_reward(1.0);
+    assert!(receptors(&brain, pa) > ab);
+    assert_eq!(receptors(&brain, pc), cb);
+    let ab = receptors(&brain, pa);
+    brain.apply_regional_reward(-1.0, pc, 0.5);
+    assert_eq!(receptors(&brain, pa), ab);
+    brain.apply_regional_reward(-1.0, pb, 0.5);
+    assert!(receptors(&brain, pa) < ab);
+}
+
+#[test]
+fn test_sensor_effector_modes() {
+    let mut rng = BrainRng::from_u64(42);
+    let mut sensor = Sensor::new(
+        Default::default(),
+        Default::default(),
+        SensorMode::RateCoded {
+            max_rate: 10.0,
+            spike_potential: 2.0,
+        },
+    );
+    sensor.value = 1.0;
+    let total = (0..100)
+        .filter_map(|_| sensor.process(0.01, &mut rng))
+        .sum::<f64>();
+    assert!((total - 20.0).abs() <= 2.0);
+    sensor.mode = SensorMode::Poisson {
+        max_rate: 10.0,
+        spike_potential: 2.0,
+    };
+    let total = (0..1000)
+        .filter_map(|_| sensor.process(0.01, &mut rng))
+        .sum::<f64>();
+    assert!(total > 100.0 && total < 300.0);
+    sensor.mode = SensorMode::ConstantCurrent;
+    assert_eq!(sensor.process(0.5, &mut rng), Some(0.5));
+
+    let mut effector = Effector::new(
+        Default::default(),
+        Default::default(),
+        EffectorMode::SpikeCount { window: 0.5 },
+    );
+    for i in 0..10 {
+        effector.process(0.0, i % 2 == 0, i as f64 * 0.1, 0.1);
+    }
+    assert_eq!(effector.potential(), 2.0);
+    effector.mode = EffectorMode::MaxHold { decay: 1.0 };
+    effector.process(5.0, false, 0.0, 0.1);
+    effector.process(0.0, false, 0.0, 0.1);
+    assert!((effector.potential() - 4.9).abs() < 1.0e-9);
+    effector.reset();
+    effector.mode = EffectorMode::MovingAverage { time_constant: 1.0 };
+    effector.process(1.0, false, 0.0, 0.1);
+    assert!(effector.potential() > 0.0 && effector.potential() < 1.0);
+
+    let mut brain = Brain::new();
+    let n1 = brain.create_neuron(Position {
+        x: 0.0,
+        y: 0.0,
+        z: 0.0,
+    });
+    let n2 = brain.create_neuron(Position {
+        x: 0.1,
+        y: 0.0,
+        z: 0.0,
+    });
+    brain.bind_neurons(n1, n2).unwrap();
+    let s1 = brain.create_sensor(n1).unwrap();
+    let e1 = brain.create_effector(n2).unwrap();
+    brain
+        .set_sensor_mode(
+            s1,
+            SensorMode::RateCoded {
+                max_rate: 20.0,
+                spike_potential: 2.0,
+            },
+        )
+        .unwrap();
+    brain.sensor_set_value(s1, 1.0).unwrap();
+    brain
+        .set_effector_mode(e1, EffectorMode::SpikeCount { window: 1.0 })
+        .unwrap();
+    for _ in 0..100 {
+        brain.process(0.01).unwrap();
+    }
+    let count = brain.effector_potential(e1).unwrap();
+    assert!(count > 0.0);
+    assert_eq!(brain.effector_potential_release(e1).unwrap(), count);
+    // only raw potential is cleared on release, spikes window is kept.
+    assert_eq!(brain.effector_potential(e1).unwrap(), count);
+    brain
+        .set_effector_mode(e1, EffectorMode::Potential)
+        .unwrap();
+    brain.process(0.01).unwrap();
+    brain.effector_potential_release(e1).unwrap();
+    assert_eq!(brain.effector_potential(e1).unwrap(), 0.0);
+}
+
+// sensor neuron fires once into two silent neurons, whose potential then decays.
+fn make_continuous_effectors_brain() -> (Brain, SensorID, EffectorID, EffectorID) {
+    let mut brain = Brain::new();
+    brain
+        .config_mut()
+        .leaky_integrate_and_fire
+        .action_potential_treshold = 100.0;
+    let position = |x| Position { x, y: 0.0, z: 0.0 };
+    let n1 = brain.create_neuron(position(0.0));
+    let n2 = brain.create_neuron(position(0.1));
+    let n3 = brain.create_neuron(position(-0.1));
+    for id in &[n2, n3] {
+        brain
+            .set_neuron_model(*id, Some(NeuronModelType::LeakyIntegrateAndFire))
+            .unwrap();
+        brain.bind_neurons(n1, *id).unwrap();
+    }
+    let sensor = brain.create_sensor(n1).unwrap();
+    let max_hold = brain.create_effector(n2).unwrap();
+    let moving_average = brain.create_effector(n3).unwrap();
+    brain
+        .set_effector_mode(max_hold, EffectorMode::MaxHold { decay: 0.0 })
+        .unwrap();
+    brain
+        .set_effector_mode(
+            moving_average,
+            EffectorMode::MovingAverage { time_constant: 1.0 },
+        )
+        .unwrap();
+    brain.sensor_trigger_impulse(sensor, 4.0).unwrap();
+    (brain, sensor, max_hold, moving_average)
+}
+
+#[test]
+fn test_effector_release() {
+    let (mut brain, _, max_hold, moving_average) = make_continuous_effectors_brain();
+    let mut held = vec![];
+    let mut averaged = vec![];
+    for _ in 0..50 {
+        brain.process(0.01).unwrap();
+        held.push(brain.effector_potential_release(max_hold).unwrap());
+        averaged.push(brain.effector_potential_release(moving_average).unwrap());
+    }
+    let peak = held.iter().cloned().fold(0.0, f64::max);
+    assert!(peak > 0.0);
+    assert!(held.windows(2).all(|w| w[1] >= w[0]));
+    assert_eq!(*held.last().unwrap(), peak);
+    assert!(averaged.windows(2).all(|w| w[1] >= w[0]));
+    assert!(*averaged.last().unwrap() > 0.1);
+}
+
+#[test]
+fn test_labels() {
+    let mut brain = BrainBuilder::new()
+        .neurons(10)
+        .connections(20)
+        .sensors(2)
+        .effectors(2)
+        .seed(42)
+        .build();
+    let sensors = brain.get_sensors();
+    let effectors = brain.get_effectors();
+    brain
+        .set_sensor_label(sensors[0], Label::new().name("eye").tag("vision"))
+        .unwrap();
+    brain
+        .set_sensor_label(sensors[1], Label::new().tag("vision"))
+        .unwrap();
+    brain
+        .set_effector_label(effectors[0], Label::new().name("leg").meta("side", "left"))
+        .unwrap();
+    assert_eq!(brain.sensor_by_name("eye"), Some(sensors[0]));
+    assert_eq!(brain.sensor_by_name("ear"), None);
+    assert_eq!(brain.sensors_by_tag("vision").len(), 2);
+    assert_eq!(brain.effector_by_name("leg"), Some(effectors[0]));
+    assert_eq!(
+        brain
+            .effector(effectors[0])
+            .unwrap()
+            .label()
+            .metadata
+            .get("side")
+            .map(|v| v.as_str()),
+        Some("left")
+    );
+
+    let duplicate = brain.duplicate();
+    assert_eq!(duplicate.sensor_by_name("eye"), Some(sensors[0]));
+    assert_eq!(duplicate.effector_by_name("leg"), Some(effectors[0]));
+    let merged = brain.merge(&duplicate);
+    let offspring = OffspringBuilder::new().seed(7).build_mutated(&brain);
+    for child in &[merged, offspring] {
+        for id in child.get_sensors() {
+            if let Some(sensor) = brain.sensor(id) {
+                assert_eq!(child.sensor(id).unwrap().label(), sensor.label());
+            }
+        }
+        for id in child.get_effectors() {
+            if let Some(effector) = brain.effector(id) {
+                assert_eq!(child.effector(id).unwrap().label(), effector.label());
+            }
+        }
+    }
+}
+
+#[test]
+fn test_io_layout() {
+    let mut brain = BrainBuilder::new()
+        .neurons(10)
+        .connections(20)
+        .sensors(3)
+        .effectors(2)
+        .seed(42)
+        .build();
+    let sensors = brain.get_sensors();
+    brain
+        .set_sensor_label(sensors[2], Label::new().name("a"))
+        .unwrap();
+    let layout = brain.io_layout();
+    assert_eq!(layout.sensors.len(), 3);
+    assert_eq!(layout.effectors.len(), 2);
+    assert_eq!(layout.sensors[0], sensors[2]);
+    assert!(layout.sensors[1] < layout.sensors[2]);
+    assert_eq!(brain.duplicate().io_layout(), layout);
+
+    brain
+        .set_sensor_mode(layout.sensors[1], SensorMode::ConstantCurrent)
+        .unwrap();
+    assert!(brain.feed(&[1.0, 2.0]).is_err());
+    brain.feed(&[1.0, 2.0, 3.0]).unwrap();
+    assert_eq!(brain.sensor(layout.sensors[1]).unwrap().value(), 2.0);
+
+    let mut outputs = [0.0; 2];
+    assert!(brain.read_outputs(&mut outputs[..1]).is_err());
+    brain.read_outputs(&mut outputs).unwrap();
+
+    let (mut brain, _, max_hold, moving_average) = make_continuous_effectors_brain();
+    brain
+        .set_effector_label(max_hold, Label::new().name("max_hold"))
+        .unwrap();
+    brain
+        .set_effector_label(moving_average, Label::new().name("moving_average"))
+        .unwrap();
+    let mut frames = vec![];
+    for _ in 0..50 {
+        brain.process(0.01).unwrap();
+        brain.read_outputs(&mut outputs).unwrap();
+        frames.push(outputs);
+    }
+    assert!(frames[frames.len() - 1][0] > 0.0);
+    assert!(frames.windows(2).all(|w| w[1][0] >= w[0][0]));
+    assert!(frames.windows(2).all(|w| w[1][1] >= w[0][1]));
+    assert!(frames[frames.len() - 1][1] > 0.1);
+    assert_eq!(
+        brain.effector_potential(max_hold).unwrap(),
+        frames[frames.len() - 1][0]
+    );
+}
+
+#[test]
+fn test_events() {
+    let mut brain = Brain::new();
+    let n1 = brain.create_neuron(Position {
+        x: 0.0,
+        y: 0.0,
+        z: 0.0,
+    });
+    let n2 = brain.create_neuron(Position {
+        x: 0.1,
+        y: 0.0,
+        z: 0.0,
+    });
+    let s1 = brain.create_sensor(n1).unwrap();
+    brain.bind_neurons(n1, n2).unwrap();
+    let e1 = brain.create_effector(n2).unwrap();
+    assert!(brain.drain_events().is_empty());
+
+    brain.set_events_enabled(true);
+    brain.sensor_trigger_impulse(s1, 10.0).unwrap();
+    for _ in 0..10 {
+        brain.process(0.1).unwrap();
+    }
+    let events = brain.drain_events();
+    assert!(events.contains(&BrainEvent::NeuronFired(n1)));
+    assert!(events.iter().any(
+        |e| matches!(e, BrainEvent::ImpulseDelivered(from, to, _) if *from == n1 && *to == n2)
+    ));
+    assert!(brain.drain_events().is_empty());
+
+    brain.kill_neuron(n2).unwrap();
+    let events = brain.drain_events();
+    assert!(events.contains(&BrainEvent::EffectorRemoved(e1)));
+    assert!(events.contains(&BrainEvent::NeuronDied(n2)));
+
+    brain.clear();
+    let events = brain.drain_events();
+    assert_eq!(
+        events,
+        vec![BrainEvent::SensorRemoved(s1), BrainEvent::NeuronDied(n1)]
+    );
+}
+
+#[test]
+fn test_recorder() {
+    let mut brain = Brain::new();
+    let n1 = brain.create_neuron(Position {
+        x: 0.0,
+        y: 0.0,
+        z: 0.0,
+    });
+    let n2 = brain.create_neuron(Position {
+        x: 0.1,
+        y: 0.0,
+        z: 0.0,
+    });
+    let s1 = brain.create_sensor(n1).unwrap();
+    brain.bind_neurons(n1, n2).unwrap();
+    let e1 = brain.create_effector(n2).unwrap();
+    let mut recorder = Recorder::new()
+        .neurons(vec![n1, n2])
+        .effectors(vec![e1])
+        .stats(true);
+    brain.sensor_trigger_impulse(s1, 10.0).unwrap();
+    for _ in 0..10 {
+        brain.process(0.1).unwrap();
+        recorder.record(&brain);
+    }
+    assert_eq!(recorder.frames_count(), 10);
+    assert!(recorder.spikes().iter().any(|(_, id)| *id == n1));
+    assert_eq!(recorder.neuron_potentials(n2).unwrap().len(), 10);
+    assert_eq!(recorder.effector_outputs(e1).unwrap().len(), 10);
+    assert_eq!(recorder.stats_values("neurons_count").unwrap()[0], 2.0);
+
+    let mut csv = vec![];
+    recorder.write_csv(&mut csv).unwrap();
+    let csv = String::from_utf8(csv).unwrap();
+    assert_eq!(csv.lines().count(), 11);
+    let columns = 2 + 2 + 1 + STATS_COLUMNS.len();
+    assert!(csv.lines().all(|line| line.split(',').count() == columns));
+    let mut csv = vec![];
+    recorder.write_spikes_csv(&mut csv).unwrap();
+    assert_eq!(
+        String::from_utf8(csv).unwrap().lines().count(),
+        recorder.spikes().len() + 1
+    );
+
+    let mut recorder = recorder.neurons(vec![n1]).stats(false);
+    assert_eq!(recorder.frames_count(), 0);
+    assert!(recorder.spikes().is_empty());
+    brain.process(0.1).unwrap();
+    recorder.record(&brain);
+    assert_eq!(recorder.neuron_potentials(n1).unwrap().len(), 1);
+    assert_eq!(recorder.effector_outputs(e1).unwrap().len(), 1);
+    let mut csv = vec![];
+    recorder.write_csv(&mut csv).unwrap();
+    let csv = String::from_utf8(csv).unwrap();
+    assert_eq!(csv.lines().count(), 2);
+    assert!(csv.lines().all(|line| line.split(',').count() == 2 + 1 + 1));
+}
+
+#[test]
+fn test_activity_delta() {
+    let mut brain = BrainBuilder::new()
+        .neurons(50)
+        .connections(100)
+        .sensors(5)
+        .effectors(5)
+        .seed(42)
+        .build();
+    let mut topology = brain.build_topology();
+    let delta = brain.build_activity_delta(activity::ALL);
+    assert!(!delta.has_structural_changes());
+    assert_eq!(
+        topology.build_activity_map(&delta, activity::ALL),
+        brain.build_activity_map(activity::ALL)
+    );
+
+    let mut fired = 0;
+    for _ in 0..100 {
+        for id in brain.get_sensors() {
+            brain.sensor_trigger_impulse(id, 10.0).unwrap();
+        }
+        brain.process(0.1).unwrap();
+        let delta = brain.build_activity_delta(activity::NEURONS | activity::IMPULSES);
+        fired += delta.fired_neurons.len();
+        topology.apply(&delta);
+        let map = topology.build_activity_map(&delta, activity::IMPULSES);
+        let expected = brain.build_activity_map(activity::IMPULSES);
+        assert_eq!(map.impulses.len(), expected.impulses.len());
+        assert_eq!(
+            map.inhibitory_impulses.len(),
+            expected.inhibitory_impulses.len()
+        );
+        assert_eq!(map.impulses_potential.len(), map.impulses.len());
+    }
+    assert!(fired > 0);
+
+    // changes are logged where they happen and only the net result reaches delta.
+    let neurons = brain.get_neurons();
+    brain.kill_neuron(neurons[0]).unwrap();
+    let position = Position {
+        x: 1.0,
+        y: 2.0,
+        z: 3.0,
+    };
+    brain.set_neuron_position(neurons[1], position).unwrap();
+    brain.set_neuron_inhibitory(neurons[2], true).unwrap();
+    let temporary = brain.create_neuron(Default::default());
+    brain.bind_neurons(temporary, neurons[3]).unwrap();
+    brain.kill_neuron(temporary).unwrap();
+    let delta = brain.build_activity_delta(activity::NONE);
+    assert_eq!(delta.removed_neurons, vec![neurons[0]]);
+    assert_eq!(delta.moved_neurons, vec![(neurons[1], position)]);
+    assert_eq!(delta.inhibitory_neurons, vec![(neurons[2], true)]);
+    assert!(delta.added_neurons.is_empty());
+    assert!(delta.added_synapses.is_empty());
+    assert!(!brain
+        .build_activity_delta(activity::NONE)
+        .has_structural_changes());
+    topology.apply(&delta);
+
+    let sort = |mut topology: BrainTopology| {
+        topology.neurons.sort_by_key(|(id, _, _)| id.uuid());
+        topology
+            .synapses
+            .sort_by_key(|(from, to, _)| (from.uuid(), to.uuid()));
+        topology.sensors.sort_by_key(|(id, _)| id.uuid());
+        topology.effectors.sort_by_key(|(id, _)| id.uuid());
+        topology
+    };
+    assert_eq!(sort(topology.clone()), sort(brain.build_topology()));
+
+    brain.clear();
+    let delta = brain.build_activity_delta(activity::NONE);
+    assert_eq!(delta.removed_neurons.len(), topology.neurons.len());
+    assert_eq!(delta.removed_synapses.len(), topology.synapses.len());
+    topology.apply(&delta);
+    assert_eq!(topology, BrainTopology::default());
+}
+
+#[test]
+fn test_activity_stats() {
+    let mut brain = Brain::new();
+    let n1 = brain.create_neuron(Position {
+        x: 0.0,
+        y: 0.0,
+        z: 0.0,
+    });
+    let n2 = brain.create_neuron(Position {
+        x: 0.1,
+        y: 0.0,
+        z: 0.0,
+    });
+    let n3 = brain.create_neuron(Position {
+        x: 0.2,
+        y: 0.0,
+        z: 0.0,
+    });
+    let n4 = brain.create_neuron(Position {
+        x: 0.3,
+        y: 0.0,
+        z: 0.0,
+    });
+    brain.bind_neurons(n1, n2).unwrap();
+    brain.bind_neurons(n2, n3).unwrap();
+    brain.bind_neurons(n3, n2).unwrap();
+    brain.bind_neurons(n3, n4).unwrap();
+    let s1 = brain.create_sensor(n1).unwrap();
+    brain.create_effector(n4).unwrap();
+
+    let stats = brain.build_activity_stats();
+    assert_eq!(stats.strongly_connected_components, 3);
+    assert_eq!(stats.reachable_effectors, 1);
+    assert_eq!(stats.sensor_effector_path_length, 3.0);
+    assert_eq!(stats.incoming_neuron_connections_histogram[..3], [1, 2, 1]);
+    assert_eq!(stats.outgoing_neuron_connections_histogram[..3], [1, 2, 1]);
+    assert_eq!(stats.synapses_receptors_histogram.iter().sum::<usize>(), 4);
+    assert_eq!(stats.mean_firing_rate, 0.0);
+
+    brain.sensor_trigger_impulse(s1, 10.0).unwrap();
+    brain.process(0.1).unwrap();
+    let stats = brain.build_activity_stats();
+    assert!(stats.mean_firing_rate > 0.0);
+    assert_eq!(stats.active_neurons, 0.25);
+}
+
+#[test]
+fn test_graph_analysis() {
+    let mut brain = Brain::new();
+    let positions = (0..6)
+        .map(|i| Position {
+            x: f64::from(i) * 0.1,
+            y: 0.0,
+            z: 0.0,
+        })
+        .collect::<Vec<_>>();
+    let n = positions
+        .iter()
+        .map(|p| brain.create_neuron(*p))
+        .collect::<Vec<_>>();
+    // sensor -> n0 -> n1 -> n2 -> effector, n1 -> n3, n4 -> n5 -> effector.
+    brain.bind_neurons(n[0], n[1]).unwrap();
+    brain.bind_neurons(n[1], n[2]).unwrap();
+    brain.bind_neurons(n[1], n[3]).unwrap();
+    brain.bind_neurons(n[4], n[5]).unwrap();
+    brain.create_sensor(n[0]).unwrap();
+    brain.create_effector(n[2]).unwrap();
+    let e2 = brain.create_effector(n[5]).unwrap();
+
+    assert_eq!(reachable_from_sensors(&brain).len(), 4);
+    assert_eq!(unreachable_effectors(&brain), vec![e2]);
+    assert_eq!(unreachable_neurons(&brain), vec![n[4], n[5]]);
+    assert_eq!(dead_neurons(&brain), vec![n[3], n[4], n[5]]);
+    let dominators = dominators(&brain);
+    assert_eq!(
+        dominators[&n[2]],
+        vec![n[0], n[1], n[2]].into_iter().collect()
+    );
+    assert!(!dominators.contains_key(&n[4]));
+
+    let mut pruned = brain.duplicate();
+    assert_eq!(pruned.prune_unreachable(), 2);
+    assert_eq!(pruned.get_neurons().len(), 4);
+    assert_eq!(pruned.get_effectors().len(), 1);
+
+    for seed in 0..5 {
+        let brain = BrainBuilder::new()
+            .neurons(50)
+            .connections(60)
+            .sensors(3)
+            .effectors(5)
+            .reachable_effectors(true)
+            .seed(seed)
+            .build();
+        assert!(unreachable_effectors(&brain).is_empty());
+        let offspring = OffspringBuilder::new()
+            .new_effectors(3)
+            .reachable_effectors(true)
+            .seed(seed + 100)
+            .build_mutated(&brain);
+        assert!(unreachable_effectors(&offspring).is_empty());
+    }
+    // nothing receives sensor input, so effectors cannot be connected.
+    let brain = BrainBuilder::new()
+        .neurons(20)
+        .connections(20)
+        .sensors(0)
+        .effectors(3)
+        .reachable_effectors(true)
+        .seed(42)
+        .build();
+    assert!(!brain.get_effectors().is_empty());
+    assert_eq!(
+        unreachable_effectors(&brain).len(),
+        brain.get_effectors().len()
+    );
+}
+
+#[test]
+fn test_brain_determinism() {
+    let build = |seed| {
+        let mut brain = Brain::with_seed(seed);
+        brain.set_config(Config {
+            synapse_reconnection_range: Some(0.5),
+            synapse_new_connection_receptors: Some(1.0),
+            ..Default::default()
+        });
+        let neurons = (0..10)
+            .map(|i| {
+                brain.create_neuron(Position {
+                    x: i as f64,
+                    y: 0.0,
+                    z: 0.0,
+                })
+            })
+            .collect::<Vec<_>>();
+        for pair in neurons.windows(2) {
+            brain.bind_neurons(pair[0], pair[1]).unwrap();
+        }
+        brain.ignite_random_synapses(20, 1.0..5.0);
+        for _ in 0..50 {
+            brain.process(0.1).unwrap();
+        }
+        brain
+    };
+    assert_eq!(build(42), build(42));
+    assert_ne!(build(42), build(43));
+
+    let brain = build(7);
+    assert_eq!(
+        brain.merge_with_rng(&brain, &mut BrainRng::from_u64(1)),
+        brain.merge_with_rng(&brain, &mut BrainRng::from_u64(1))
+    );
+}
+
+#[test]
+fn test_brain_index() {
+    let mut brain = BrainBuilder::new()
+        .config(Config {
+            synapse_reconnection_range: Some(1.0),
+            synapse_new_connection_receptors: Some(1.5),
+            ..Default::default()
+        })
+        .neurons(200)
+        .connections(400)
+        .max_neurogenesis_range(5.0)
+        .sensors(10)
+        .effectors(10)
+        .seed(42)
+        .build();
+    assert!(brain.is_index_valid());
+    brain.ignite_random_synapses(100, 1.0..5.0);
+    for _ in 0..100 {
+        brain.process(0.1).unwrap();
+        assert!(brain.is_index_valid());
+    }
+    for id in brain.get_neurons().into_iter().step_by(3) {
+        brain.kill_neuron(id).unwrap();
+        assert!(brain.is_index_valid());
+    }
+    let merged = brain.merge_with_rng(&brain.duplicate(), &mut BrainRng::from_u64(1));
+    assert!(merged.is_index_valid());
+}
+
+#[test]
+fn test_brain_no_dangling_references() {
+    for seed in 0..20 {
+        let mut rng = BrainRng::from_u64(seed);
+        let mut brain = BrainBuilder::new()
+            .config(Config {
+                synapse_reconnection_range: Some(1.0),
+                synapse_new_connection_receptors: Some(1.5),
+                ..Default::default()
+            })
+            .neurons(50)
+            .connections(80)
+            .max_neurogenesis_range(5.0)
+            .sensors(5)
+            .effectors(5)
+            .seed(seed)
+            .build();
+        for _ in 0..200 {
+            let neurons = brain.get_neurons();
+            let pick = |rng: &mut BrainRng| {
+                if neurons.is_empty() {
+                    None
+                } else {
+                    Some(neurons[rng.gen_range(0, neurons.len())])
+                }
+            };
+            match rng.gen_range(0, 9) {
+                0 | 1 => brain.process(0.1).unwrap(),
+                2 => {
+                    if let Some(id) = pick(&mut rng) {
+                        brain.kill_neuron(id).unwrap();
+                    }
+                }
+                3 => {
+                    let sensors = brain.get_sensors();
+                    if !sensors.is_empty() {
+                        let id = sensors[rng.gen_range(0, sensors.len())];
+                        brain.kill_sensor(id).unwrap();
+                    }
+                }
+                4 => {
+                    let effectors = brain.get_effectors();
+                    if !effectors.is_empty() {
+                        let id = effectors[rng.gen_range(0, effectors.len())];
+                        brain.kill_effector(id).unwrap();
+                    }
+                }
+                5 => {
+                    if let (Some(from), Some(to)) = (pick(&mut rng), pick(&mut rng)) {
+                        let _ = brain.bind_neurons(from, to);
+                    }
+                }
+                6 => {
+                    if let (Some(from), Some(to)) = (pick(&mut rng), pick(&mut rng)) {
+                        let _ = brain.unbind_neurons(from, to);
+                    }
+                }
+                7 => {
+                    brain.create_neuron(Position {
+                        x: rng.gen_range(-5.0, 5.0),
+                        y: rng.gen_range(-5.0, 5.0),
+                        z: rng.gen_range(-5.0, 5.0),
+                    });
+                }
+                _ => {
+                    if let Some(id) = pick(&mut rng) {
+                        if rng.gen() {
+                            let _ = brain.create_sensor(id);
+                        } else {
+                            let _ = brain.create_effector(id);
+                        }
+                    }
+                    brain.ignite_random_synapses(5, 1.0..5.0);
+                }
+            }
+            assert!(!brain.has_dangling_references());
+            assert!(brain.is_index_valid());
+            brain.validate().unwrap();
+        }
+    }
+}
+
+#[test]
+fn test_neuron_models() {
+    let model = LeakyIntegrateAndFireModel::default();
+    let mut state = NeuronState::default();
+    model.receive(&mut state, 0.5);
+    assert_eq!(model.process(&mut state, 0.1), None);
+    assert!(state.potential < 0.5 && state.potential > 0.0);
+    model.receive(&mut state, 1.0);
+    assert!(model.process(&mut state, 0.01).is_some());
+    assert!(state.refractory > 0.0);
+    model.receive(&mut state, 10.0);
+    assert_eq!(model.process(&mut state, 0.01), None);
+    assert_eq!(state.potential, model.reset_potential);
+
+    let model = IzhikevichModel::default();
+    let mut state = NeuronState::default();
+    for _ in 0..10 {
+        assert_eq!(model.process(&mut state, 0.01), None);
+    }
+    model.receive(&mut state, 5.0);
+    assert!((0..10).any(|_| model.process(&mut state, 0.01).is_some()));
+
+    for neuron_model in &[
+        NeuronModelType::Threshold,
+        NeuronModelType::LeakyIntegrateAndFire,
+        NeuronModelType::Izhikevich,
+    ] {
+        let mut brain = Brain::with_seed(42);
+        brain.set_config(Config {
+            neuron_model: *neuron_model,
+            ..Default::default()
+        });
+        let neurons = (0..3)
+            .map(|i| {
+                brain.create_neuron(Position {
+                    x: i as f64 * 0.1,
+                    y: 0.0,
+                    z: 0.0,
+                })
+            })
+            .collect::<Vec<_>>();
+        brain.bind_neurons(neurons[0], neurons[1]).unwrap();
+        brain.bind_neurons(neurons[1], neurons[2]).unwrap();
+        let sensor = brain.create_sensor(neurons[0]).unwrap();
+        let mut fired = false;
+        for _ in 0..50 {
+            brain.sensor_trigger_impulse(sensor, 2.0).unwrap();
+            brain.process(0.01).unwrap();
+            fired = fired || brain.get_impulses_count() > 0;
+        }
+        assert!(fired);
+    }
+}
+
+#[test]
+fn test_brain_scale() {
+    let mut brain = Brain::with_seed(42);
+    let neurons = (0..100_000)
+        .map(|i| {
+            brain.create_neuron(Position {
+                x: i as f64,
+                y: 0.0,
+                z: 0.0,
+            })
+        })
+        .collect::<Vec<_>>();
+    for pair in neurons.windows(2) {
+        brain.bind_neurons(pair[0], pair[1]).unwrap();
+    }
+    brain.ignite_random_synapses(10_000, 1.0..5.0);
+    for _ in 0..10 {
+        brain.process(0.1).unwrap();
+    }
+    assert_eq!(brain.get_neurons().len(), 100_000);
+}
+
 #[test]
 fn test_brain_builder() {
     let _brain = BrainBuilder::new()
@@ -113,3 +1059,298 @@ fn test_offspring_builder() {
     //     brain.get_effectors().len()
     // );
 }
+
+#[test]
+fn test_compatibility() {
+    let brain_builder = BrainBuilder::new()
+        .neurons(20)
+        .connections(40)
+        .sensors(2)
+        .effectors(2);
+    let brain = brain_builder.clone().seed(1).build();
+    let duplicate = brain.duplicate();
+    let other = brain_builder.seed(2).build();
+    let compatibility = Compatibility::default();
+
+    assert_eq!(
+        align_neurons(&brain, &duplicate, 0.0).len(),
+        brain.neurons().len()
+    );
+    assert_eq!(compatibility.distance(&brain, &duplicate), 0.0);
+    assert!(compatibility.distance(&brain, &other) > 0.0);
+    // unrelated brains are aligned only by position: every pair lies within range and no
+    // unpaired neurons within range are left.
+    let pairs = align_neurons(&brain, &other, 1.0);
+    assert!(!pairs.is_empty());
+    for (na, nb) in &pairs {
+        let pa = brain.neuron(*na).unwrap().position();
+        let pb = other.neuron(*nb).unwrap().position();
+        assert!(pa.distance(pb) <= 1.0);
+    }
+    for na in brain.neurons() {
+        for nb in other.neurons() {
+            if na.position().distance(nb.position()) <= 1.0 {
+                assert!(pairs.iter().any(|(a, b)| *a == na.id() || *b == nb.id()));
+            }
+        }
+    }
+
+    let child = brain.crossover_with_rng(&other, 1.0, &mut BrainRng::from_u64(42));
+    child.validate().unwrap();
+    assert_eq!(child.neurons().len(), brain.neurons().len());
+    assert_eq!(child.synapses().len(), brain.synapses().len());
+    assert_eq!(child.get_sensors(), brain.get_sensors());
+    assert_eq!(child.get_effectors(), brain.get_effectors());
+    let positions = |b: &Brain| b.neurons().iter().map(|n| n.position()).collect::<Vec<_>>();
+    assert_eq!(positions(&child), positions(&brain));
+
+    let child = OffspringBuilder::new()
+        .new_sensors(0)
+        .new_effectors(0)
+        .homology_range(Some(1.0))
+        .seed(42)
+        .build_merged(&brain, &other);
+    child.validate().unwrap();
+    assert!(child.neurons().len() >= brain.neurons().len());
+}
+
+#[test]
+fn test_lineage() {
+    let brain = BrainBuilder::new()
+        .neurons(20)
+        .connections(40)
+        .sensors(2)
+        .effectors(2)
+        .seed(42)
+        .build();
+    assert!(brain.parents().is_empty());
+    for neuron in brain.neurons() {
+        assert_eq!(neuron.lineage(), LineageID::from_neuron(neuron.id()));
+    }
+    let lineages = |b: &Brain| b.neurons().iter().map(|n| n.lineage()).collect::<Vec<_>>();
+    let synapse_lineages = |b: &Brain| b.synapses().iter().map(|s| s.lineage()).collect::<Vec<_>>();
+
+    let mut duplicate = brain.duplicate();
+    assert_eq!(duplicate.parents(), &[brain.id()]);
+    assert_eq!(lineages(&duplicate), lineages(&brain));
+    assert_eq!(synapse_lineages(&duplicate), synapse_lineages(&brain));
+    // synapse recreated between homologous neurons gets the same lineage.
+    let (source, target) = {
+        let synapse = &duplicate.synapses()[0];
+        (synapse.source(), synapse.target())
+    };
+    let lineage = duplicate.synapses()[0].lineage();
+    duplicate.unbind_neurons(source, target).unwrap();
+    duplicate.bind_neurons(source, target).unwrap();
+    assert_eq!(duplicate.synapses().last().unwrap().lineage(), lineage);
+
+    let mutated = OffspringBuilder::new()
+        .new_neurons(5)
+        .new_connections(10)
+        .seed(42)
+        .build_mutated(&brain);
+    assert_eq!(mutated.parents(), &[brain.id()]);
+    let inherited = lineages(&brain);
+    for neuron in mutated.neurons() {
+        assert!(
+            inherited.contains(&neuron.lineage())
+                || neuron.lineage() == LineageID::from_neuron(neuron.id())
+        );
+    }
+
+    let merged = brain.merge(&mutated);
+    assert_eq!(merged.parents(), &[brain.id(), mutated.id()]);
+    let inherited = lineages(&mutated);
+    assert!(merged
+        .neurons()
+        .iter()
+        .all(|n| inherited.contains(&n.lineage())));
+    assert_eq!(
+        align_neurons(&brain, &mutated, 0.0).len(),
+        mutated
+            .neurons()
+            .iter()
+            .filter(|n| lineages(&brain).contains(&n.lineage()))
+            .count()
+    );
+}
+
+#[test]
+fn test_config_mutation() {
+    let config = Config {
+        synapse_reconnection_range: Some(2.0),
+        ..Default::default()
+    };
+    let mut rng = BrainRng::from_u64(42);
+    assert_eq!(config.mutate(&ConfigMutation::default(), &mut rng), config);
+
+    let mutation = ConfigMutation {
+        propagation_speed: Some(ScalarMutation::new(1.0, 10.0, 0.5..2.0)),
+        default_receptors: Some(ScalarMutation::new(1.0, 1.0, 0.0..3.0)),
+        neuron_potential_decay: Some(ScalarMutation::new(0.0, 1.0, 0.0..10.0)),
+        synapse_reconnection_range: Some(OptionMutation::new(
+            1.0,
+            ScalarMutation::new(0.0, 0.0, 1.0..5.0),
+        )),
+        synapse_overdose_receptors: Some(OptionMutation::new(
+            1.0,
+            ScalarMutation::new(0.0, 0.0, 1.0..5.0),
+        )),
+        ..Default::default()
+    };
+    for _ in 0..10 {
+        let mutated = config.mutate(&mutation, &mut rng);
+        assert_ne!(mutated.propagation_speed, config.propagation_speed);
+        assert!(mutated.propagation_speed >= 0.5 && mutated.propagation_speed <= 2.0);
+        assert!(mutated.default_receptors.start <= mutated.default_receptors.end);
+        assert!(mutated.default_receptors.start >= 0.0 && mutated.default_receptors.end <= 3.0);
+        assert_eq!(
+            mutated.neuron_potential_decay,
+            config.neuron_potential_decay
+        );
+        assert_eq!(mutated.synapse_reconnection_range, None);
+        let overdose = mutated.synapse_overdose_receptors.unwrap();
+        assert!((1.0..5.0).contains(&overdose));
+    }
+
+    // neuron models, plasticity and learning parameters evolve too.
+    let models_mutation = ConfigMutation {
+        leaky_integrate_and_fire: LeakyIntegrateAndFireMutation {
+            action_potential_treshold: Some(ScalarMutation::new(1.0, 0.5, 0.5..1.5)),
+            time_constant: Some(ScalarMutation::new(1.0, 0.5, 0.1..2.0)),
+            ..Default::default()
+        },
+        izhikevich: IzhikevichMutation {
+            peak_potential: Some(ScalarMutation::new(1.0, 5.0, 20.0..40.0)),
+            ..Default::default()
+        },
+        stdp: StdpMutation {
+            potentiation: Some(ScalarMutation::new(1.0, 0.05, 0.0..0.2)),
+            ..Default::default()
+        },
+        eligibility_trace_time: Some(ScalarMutation::new(1.0, 0.5, 0.1..2.0)),
+        reward_learning_rate: Some(ScalarMutation::new(1.0, 0.05, 0.0..0.2)),
+        firing_rate_time: Some(ScalarMutation::new(1.0, 0.5, 0.1..2.0)),
+        ..Default::default()
+    };
+    for _ in 0..10 {
+        let mutated = config.mutate(&models_mutation, &mut rng);
+        let lif = mutated.leaky_integrate_and_fire;
+        assert_ne!(
+            lif.action_potential_treshold,
+            config.leaky_integrate_and_fire.action_potential_treshold
+        );
+        assert!((0.5..=1.5).contains(&lif.action_potential_treshold));
+        assert!((0.1..=2.0).contains(&lif.time_constant));
+        assert_eq!(
+            lif.reset_potential,
+            config.leaky_integrate_and_fire.reset_potential
+        );
+        assert_ne!(
+            mutated.izhikevich.peak_potential,
+            config.izhikevich.peak_potential
+        );
+        assert!((20.0..=40.0).contains(&mutated.izhikevich.peak_potential));
+        assert_eq!(mutated.izhikevich.a, config.izhikevich.a);
+        assert!((0.0..=0.2).contains(&mutated.stdp.potentiation));
+        assert_eq!(mutated.stdp.depression, config.stdp.depression);
+        assert_ne!(
+            mutated.eligibility_trace_time,
+            config.eligibility_trace_time
+        );
+        assert!((0.1..=2.0).contains(&mutated.eligibility_trace_time));
+        assert!((0.0..=0.2).contains(&mutated.reward_learning_rate));
+        assert!((0.1..=2.0).contains(&mutated.firing_rate_time));
+    }
+
+    let source = BrainBuilder::new()
+        .neurons(5)
+        .connections(10)
+        .seed(42)
+        .build();
+    let brain = OffspringBuilder::new()
+        .config_mutation(mutation)
+        .seed(42)
+        .build_mutated(&source);
+    assert_ne!(
+        brain.config().propagation_speed,
+        source.config().propagation_speed
+    );
+}
+
+#[test]
+fn test_offspring_removal() {
+    let source = BrainBuilder::new()
+        .neurons(30)
+        .connections(60)
+        .sensors(4)
+        .effectors(4)
+        .seed(42)
+        .build();
+    let brain = OffspringBuilder::new()
+        .new_neurons(0)
+        .new_connections(0)
+        .new_sensors(0)
+        .new_effectors(0)
+        .kill_neurons(5)
+        .kill_connections(5)
+        .kill_sensors(10)
+        .kill_effectors(1)
+        .min_sensors(2)
+        .min_effectors(3)
+        .seed(42)
+        .build_mutated(&source);
+    brain.validate().unwrap();
+    assert!(brain.neurons().len() <= source.neurons().len() - 5);
+    assert!(brain.synapses().len() < source.synapses().len());
+    assert_eq!(brain.get_sensors().len(), 2);
+    assert_eq!(brain.get_effectors().len(), 3);
+
+    let brain = OffspringBuilder::new()
+        .new_neurons(0)
+        .new_connections(0)
+        .new_sensors(0)
+        .new_effectors(0)
+        .reroute_connections(10)
+        .move_neurons(10)
+        .move_range(1.0)
+        .receptors_jitter(0.5)
+        .seed(42)
+        .build_mutated(&source);
+    brain.validate().unwrap();
+    assert_eq!(brain.synapses().len(), source.synapses().len());
+    for synapse in brain.synapses() {
+        assert!(synapse.receptors() >= 0.0);
+        let source = brain.neuron(synapse.source()).unwrap().position();
+        let target = brain.neuron(synapse.target()).unwrap().position();
+        assert!((synapse.distance() - source.distance(target)).abs() < 1e-9);
+    }
+    let positions = |b: &Brain| b.neurons().iter().map(|n| n.position()).collect::<Vec<_>>();
+    assert_ne!(positions(&brain), positions(&source));
+
+    // removal of all neurons leaves nothing to grow new structure from.
+    let mut source = Brain::with_seed(42);
+    let n1 = source.create_neuron(Default::default());
+    let n2 = source.create_neuron(Position {
+        x: 1.0,
+        y: 0.0,
+        z: 0.0,
+    });
+    source.bind_neurons(n1, n2).unwrap();
+    source.create_sensor(n1).unwrap();
+    source.create_effector(n2).unwrap();
+    let builder = OffspringBuilder::new()
+        .new_sensors(2)
+        .new_effectors(2)
+        .new_connections(2)
+        .kill_sensors(5)
+        .kill_effectors(5)
+        .kill_neurons(5)
+        .seed(42);
+    let brain = builder.clone().build_mutated(&source);
+    brain.validate().unwrap();
+    assert!(brain.neurons().is_empty());
+    let brain = builder.build_merged(&source, &source);
+    brain.validate().unwrap();
+    assert!(brain.neurons().is_empty());
+}
@@ -0,0 +1,285 @@
+use crate::brain::{activity, BrainActivityMap};
+use crate::effector::EffectorID;
+use crate::neuron::{NeuronID, Position};
+use crate::sensor::SensorID;
+use crate::Scalar;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+/// Static structure of brain, kept up to date by applying activity deltas.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BrainTopology {
+    // (neuron, position, inhibitory)
+    pub neurons: Vec<(NeuronID, Position, bool)>,
+    // (from, to, inhibitory)
+    pub synapses: Vec<(NeuronID, NeuronID, bool)>,
+    // (sensor, target)
+    pub sensors: Vec<(SensorID, NeuronID)>,
+    // (effector, source)
+    pub effectors: Vec<(EffectorID, NeuronID)>,
+}
+
+impl BrainTopology {
+    /// Applies structural changes of delta (used by consumers that do not own brain).
+    pub fn apply(&mut self, delta: &BrainActivityDelta) {
+        if !delta.removed_neurons.is_empty() {
+            let removed = delta.removed_neurons.iter().collect::<HashSet<_>>();
+            self.neurons.retain(|(id, _, _)| !removed.contains(id));
+        }
+        if !delta.removed_synapses.is_empty() {
+            let removed = delta.removed_synapses.iter().collect::<HashSet<_>>();
+            self.synapses
+                .retain(|(from, to, _)| !removed.contains(&(*from, *to)));
+        }
+        if !delta.removed_sensors.is_empty() {
+            let removed = delta.removed_sensors.iter().collect::<HashSet<_>>();
+            self.sensors.retain(|(id, _)| !removed.contains(id));
+        }
+        if !delta.removed_effectors.is_empty() {
+            let removed = delta.removed_effectors.iter().collect::<HashSet<_>>();
+            self.effectors.retain(|(id, _)| !removed.contains(id));
+        }
+        self.neurons.extend(delta.added_neurons.iter().cloned());
+        self.synapses.extend(delta.added_synapses.iter().cloned());
+        self.sensors.extend(delta.added_sensors.iter().cloned());
+        self.effectors.extend(delta.added_effectors.iter().cloned());
+        if !delta.moved_neurons.is_empty() {
+            let moved = delta
+                .moved_neurons
+                .iter()
+                .cloned()
+                .collect::<HashMap<_, _>>();
+            for (id, position, _) in &mut self.neurons {
+                if let Some(p) = moved.get(id) {
+                    *position = *p;
+                }
+            }
+        }
+        if !delta.inhibitory_neurons.is_empty() {
+            let changed = delta
+                .inhibitory_neurons
+                .iter()
+                .cloned()
+                .collect::<HashMap<_, _>>();
+            for (id, _, inhibitory) in &mut self.neurons {
+                if let Some(value) = changed.get(id) {
+                    *inhibitory = *value;
+                }
+            }
+            for (from, _, inhibitory) in &mut self.synapses {
+                if let Some(value) = changed.get(from) {
+                    *inhibitory = *value;
+                }
+            }
+        }
+    }
+
+    /// Builds activity map out of topology and dynamic activity of delta, so consumers that keep
+    /// topology up to date do not have to query whole brain every frame.
+    /// NOTE: Delta has to be built with the same flags to provide potentials and receptors.
+    pub fn build_activity_map(&self, delta: &BrainActivityDelta, flags: usize) -> BrainActivityMap {
+        let positions = self
+            .neurons
+            .iter()
+            .map(|(id, position, _)| (*id, *position))
+            .collect::<HashMap<_, _>>();
+        let position = |id| positions.get(&id).cloned().unwrap_or_default();
+        let inhibitory = self
+            .neurons
+            .iter()
+            .filter(|(_, _, inhibitory)| *inhibitory)
+            .map(|(id, _, _)| *id)
+            .collect::<HashSet<_>>();
+        let mut result = BrainActivityMap::default();
+        if flags & activity::CONNECTIONS != 0 {
+            let receptors = delta
+                .synapses_receptors
+                .iter()
+                .map(|(from, to, receptors)| ((*from, *to), *receptors))
+                .collect::<HashMap<_, _>>();
+            for (from, to, inhibitory) in &self.synapses {
+                let item = (
+                    position(*from),
+                    position(*to),
+                    receptors.get(&(*from, *to)).cloned().unwrap_or(0.0),
+                );
+                if *inhibitory {
+                    result.inhibitory_connections.push(item);
+                } else {
+                    result.connections.push(item);
+                }
+            }
+        }
+        if flags & activity::IMPULSES != 0 {
+            for ((from, to, factor), potential) in
+                delta.impulses.iter().zip(delta.impulses_potential.iter())
+            {
+                let item = (position(*from), position(*to), *factor);
+                if inhibitory.contains(from) {
+                    result.inhibitory_impulses.push(item);
+                    result.inhibitory_impulses_potential.push(*potential);
+                } else {
+                    result.impulses.push(item);
+                    result.impulses_potential.push(*potential);
+                }
+            }
+        }
+        if flags & activity::SENSORS != 0 {
+            result.sensors = self
+                .sensors
+                .iter()
+                .map(|(_, target)| position(*target))
+                .collect();
+        }
+        if flags & activity::EFFECTORS != 0 {
+            result.effectors = self
+                .effectors
+                .iter()
+                .map(|(_, source)| position(*source))
+                .collect();
+        }
+        if flags & activity::NEURONS != 0 {
+            let potentials = delta
+                .neurons_potential
+                .iter()
+                .cloned()
+                .collect::<HashMap<_, _>>();
+            for (id, position, inhibitory) in &self.neurons {
+                let potential = potentials.get(id).cloned().unwrap_or(0.0);
+                if *inhibitory {
+                    result.inhibitory_neurons.push(*position);
+                    result.inhibitory_neurons_potential.push(potential);
+                } else {
+                    result.neurons.push(*position);
+                    result.neurons_potential.push(potential);
+                }
+            }
+        }
+        result
+    }
+}
+
+/// Changes of brain since last delta was built, plus current dynamic activity.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BrainActivityDelta {
+    // (neuron, position, inhibitory)
+    pub added_neurons: Vec<(NeuronID, Position, bool)>,
+    pub removed_neurons: Vec<NeuronID>,
+    // (from, to, inhibitory)
+    pub added_synapses: Vec<(NeuronID, NeuronID, bool)>,
+    // (from, to)
+    pub removed_synapses: Vec<(NeuronID, NeuronID)>,
+    // (sensor, target)
+    pub added_sensors: Vec<(SensorID, NeuronID)>,
+    pub removed_sensors: Vec<SensorID>,
+    // (effector, source)
+    pub added_effectors: Vec<(EffectorID, NeuronID)>,
+    pub removed_effectors: Vec<EffectorID>,
+    /// New positions of moved neurons.
+    #[serde(default)]
+    pub moved_neurons: Vec<(NeuronID, Position)>,
+    /// Neurons (together with their outgoing synapses) switched to inhibitory or excitatory.
+    #[serde(default)]
+    pub inhibitory_neurons: Vec<(NeuronID, bool)>,
+    /// Neurons that fired in last processing step.
+    pub fired_neurons: Vec<NeuronID>,
+    #[serde(default)]
+    pub neurons_potential: Vec<(NeuronID, Scalar)>,
+    // (from, to, receptors)
+    #[serde(default)]
+    pub synapses_receptors: Vec<(NeuronID, NeuronID, Scalar)>,
+    // (from, to, factor)
+    pub impulses: Vec<(NeuronID, NeuronID, Scalar)>,
+    // potential of each of `impulses`
+    #[serde(default)]
+    pub impulses_potential: Vec<Scalar>,
+}
+
+impl BrainActivityDelta {
+    /// Tells if delta changes brain structure.
+    pub fn has_structural_changes(&self) -> bool {
+        !self.added_neurons.is_empty()
+            || !self.removed_neurons.is_empty()
+            || !self.added_synapses.is_empty()
+            || !self.removed_synapses.is_empty()
+            || !self.added_sensors.is_empty()
+            || !self.removed_sensors.is_empty()
+            || !self.added_effectors.is_empty()
+            || !self.removed_effectors.is_empty()
+            || !self.moved_neurons.is_empty()
+            || !self.inhibitory_neurons.is_empty()
+    }
+
+    // change log methods used by brain, so that items added and removed between two deltas
+    // never reach consumers.
+
+    pub(crate) fn neuron_added(&mut self, id: NeuronID, position: Position, inhibitory: bool) {
+        self.added_neurons.push((id, position, inhibitory));
+    }
+
+    pub(crate) fn neuron_removed(&mut self, id: NeuronID) {
+        self.moved_neurons.retain(|(n, _)| *n != id);
+        self.inhibitory_neurons.retain(|(n, _)| *n != id);
+        if let Some(index) = self.added_neurons.iter().position(|(n, _, _)| *n == id) {
+            self.added_neurons.remove(index);
+        } else {
+            self.removed_neurons.push(id);
+        }
+    }
+
+    pub(crate) fn neuron_moved(&mut self, id: NeuronID, position: Position) {
+        if let Some(item) = self.moved_neurons.iter_mut().find(|(n, _)| *n == id) {
+            item.1 = position;
+        } else {
+            self.moved_neurons.push((id, position));
+        }
+    }
+
+    pub(crate) fn neuron_inhibitory_changed(&mut self, id: NeuronID, inhibitory: bool) {
+        if let Some(item) = self.inhibitory_neurons.iter_mut().find(|(n, _)| *n == id) {
+            item.1 = inhibitory;
+        } else {
+            self.inhibitory_neurons.push((id, inhibitory));
+        }
+    }
+
+    pub(crate) fn synapse_added(&mut self, from: NeuronID, to: NeuronID, inhibitory: bool) {
+        self.added_synapses.push((from, to, inhibitory));
+    }
+
+    pub(crate) fn synapse_removed(&mut self, from: NeuronID, to: NeuronID) {
+        if let Some(index) = self
+            .added_synapses
+            .iter()
+            .position(|(f, t, _)| *f == from && *t == to)
+        {
+            self.added_synapses.remove(index);
+        } else {
+            self.removed_synapses.push((from, to));
+        }
+    }
+
+    pub(crate) fn sensor_added(&mut self, id: SensorID, target: NeuronID) {
+        self.added_sensors.push((id, target));
+    }
+
+    pub(crate) fn sensor_removed(&mut self, id: SensorID) {
+        if let Some(index) = self.added_sensors.iter().position(|(s, _)| *s == id) {
+            self.added_sensors.remove(index);
+        } else {
+            self.removed_sensors.push(id);
+        }
+    }
+
+    pub(crate) fn effector_added(&mut self, id: EffectorID, source: NeuronID) {
+        self.added_effectors.push((id, source));
+    }
+
+    pub(crate) fn effector_removed(&mut self, id: EffectorID) {
+        if let Some(index) = self.added_effectors.iter().position(|(e, _)| *e == id) {
+            self.added_effectors.remove(index);
+        } else {
+            self.removed_effectors.push(id);
+        }
+    }
+}
@@ -0,0 +1,10 @@
+extern crate psyche_core;
+extern crate rand;
+extern crate serde;
+
+#[cfg(test)]
+mod tests;
+
+pub mod population;
+pub mod selection;
+pub mod species;
@@ -0,0 +1,363 @@
+use crate::selection::{ranking, Selection};
+use crate::species::{Speciation, Species};
+use psyche_core::brain::Brain;
+use psyche_core::brain_builder::BrainBuilder;
+use psyche_core::error::*;
+use psyche_core::offspring_builder::OffspringBuilder;
+use psyche_core::rng::BrainRng;
+use psyche_core::Scalar;
+use rand::{thread_rng, Rng};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Member {
+    pub brain: Brain,
+    /// Not set until member gets evaluated.
+    pub fitness: Option<Scalar>,
+}
+
+impl Member {
+    #[inline]
+    pub fn new(brain: Brain) -> Self {
+        Self {
+            brain,
+            fitness: None,
+        }
+    }
+}
+
+/// Fitness summary of evaluated generation.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GenerationStats {
+    pub generation: usize,
+    pub best: Scalar,
+    pub mean: Scalar,
+    pub worst: Scalar,
+    #[serde(default)]
+    pub species: usize,
+}
+
+/// Population of brains evolved by selection, crossover and mutation.
+/// Every generation is first evaluated with fitness callback, then bred into the next one:
+/// - elites are moved to next generation unchanged;
+/// - remaining offspring is either merged from two selected parents (crossover) or mutated
+///   from one selected parent, both using offspring builder.
+///
+/// With speciation enabled, evaluated members are split into species of compatible brains,
+/// parents are selected by fitness shared within their species (so that new structures are
+/// not outcompeted before they get optimized) and crossover mates come from the same species.
+///
+/// NOTE: Population carries its own random number generator, so checkpoints continue with
+/// the exact same random sequence after being loaded.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Population {
+    members: Vec<Member>,
+    generation: usize,
+    selection: Selection,
+    elites: usize,
+    crossover: Scalar,
+    offspring_builder: OffspringBuilder,
+    rng: BrainRng,
+    history: Vec<GenerationStats>,
+    #[serde(default)]
+    speciation: Option<Speciation>,
+    #[serde(default)]
+    species: Vec<Species>,
+    #[serde(default)]
+    next_species_id: usize,
+}
+
+impl Default for Population {
+    fn default() -> Self {
+        Self {
+            members: vec![],
+            generation: 0,
+            selection: Default::default(),
+            elites: 1,
+            crossover: 0.0,
+            offspring_builder: Default::default(),
+            rng: Default::default(),
+            history: vec![],
+            speciation: None,
+            species: vec![],
+            next_species_id: 0,
+        }
+    }
+}
+
+impl Population {
+    pub fn new(brains: Vec<Brain>) -> Self {
+        Self {
+            members: brains.into_iter().map(Member::new).collect(),
+            ..Default::default()
+        }
+    }
+
+    /// Creates population of given size with brains made by brain builder.
+    #[inline]
+    pub fn from_builder(brain_builder: &BrainBuilder, size: usize) -> Self {
+        Self::from_builder_with_rng(brain_builder, size, &mut thread_rng())
+    }
+
+    pub fn from_builder_with_rng<R>(brain_builder: &BrainBuilder, size: usize, rng: &mut R) -> Self
+    where
+        R: Rng,
+    {
+        let brains = (0..size)
+            .map(|_| brain_builder.clone().build_with_rng(rng))
+            .collect();
+        let mut result = Self::new(brains);
+        result.rng = BrainRng::from_rng_source(rng);
+        result
+    }
+
+    pub fn selection(mut self, value: Selection) -> Self {
+        self.selection = value;
+        self
+    }
+
+    /// Sets number of the fittest members moved to next generation unchanged.
+    pub fn elites(mut self, value: usize) -> Self {
+        self.elites = value;
+        self
+    }
+
+    /// Sets probability (0..1) of offspring being merged from two parents instead of mutated.
+    pub fn crossover(mut self, value: Scalar) -> Self {
+        self.crossover = value;
+        self
+    }
+
+    pub fn offspring_builder(mut self, value: OffspringBuilder) -> Self {
+        self.offspring_builder = value;
+        self
+    }
+
+    pub fn speciation(mut self, value: Option<Speciation>) -> Self {
+        self.speciation = value;
+        self
+    }
+
+    /// Makes evolution deterministic by seeding random number generator with given value.
+    pub fn seed(mut self, value: u64) -> Self {
+        self.rng = BrainRng::from_u64(value);
+        self
+    }
+
+    #[inline]
+    pub fn members(&self) -> &[Member] {
+        &self.members
+    }
+
+    #[inline]
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
+    #[inline]
+    pub fn species(&self) -> &[Species] {
+        &self.species
+    }
+
+    /// Stats of all evaluated generations.
+    #[inline]
+    pub fn history(&self) -> &[GenerationStats] {
+        &self.history
+    }
+
+    /// Returns the fittest of evaluated members.
+    pub fn best(&self) -> Option<&Member> {
+        let fitness = self
+            .members
+            .iter()
+            .map(|m| m.fitness.unwrap_or(Scalar::NEG_INFINITY))
+            .collect::<Vec<_>>();
+        ranking(&fitness)
+            .into_iter()
+            .map(|i| &self.members[i])
+            .find(|m| m.fitness.is_some())
+    }
+
+    /// Computes fitness of every member of current generation.
+    /// Returns `None` if population is empty.
+    pub fn evaluate<F>(&mut self, mut fitness: F) -> Option<GenerationStats>
+    where
+        F: FnMut(&Brain) -> Scalar,
+    {
+        for member in &mut self.members {
+            member.fitness = Some(fitness(&member.brain));
+        }
+        self.speciate();
+        let stats = self.stats()?;
+        if let Some(last) = self.history.last_mut() {
+            if last.generation == stats.generation {
+                *last = stats;
+                return Some(stats);
+            }
+        }
+        self.history.push(stats);
+        Some(stats)
+    }
+
+    /// Stats of current generation, if all of its members are evaluated.
+    pub fn stats(&self) -> Option<GenerationStats> {
+        let fitness = self
+            .members
+            .iter()
+            .map(|m| m.fitness)
+            .collect::<Option<Vec<_>>>()?;
+        if fitness.is_empty() {
+            return None;
+        }
+        Some(GenerationStats {
+            generation: self.generation,
+            best: fitness
+                .iter()
+                .cloned()
+                .fold(Scalar::NEG_INFINITY, Scalar::max),
+            mean: fitness.iter().sum::<Scalar>() / fitness.len() as Scalar,
+            worst: fitness.iter().cloned().fold(Scalar::INFINITY, Scalar::min),
+            species: self.species.len(),
+        })
+    }
+
+    fn speciate(&mut self) {
+        let speciation = match &self.speciation {
+            Some(speciation) => speciation.clone(),
+            None => {
+                self.species.clear();
+                return;
+            }
+        };
+        for species in &mut self.species {
+            species.members.clear();
+        }
+        for (i, member) in self.members.iter().enumerate() {
+            let found = self.species.iter_mut().find(|s| {
+                speciation
+                    .compatibility
+                    .distance(&s.representative, &member.brain)
+                    <= speciation.threshold
+            });
+            match found {
+                Some(species) => species.members.push(i),
+                None => {
+                    self.species.push(Species {
+                        id: self.next_species_id,
+                        representative: member.brain.clone(),
+                        members: vec![i],
+                    });
+                    self.next_species_id += 1;
+                }
+            }
+        }
+        self.species.retain(|s| !s.members.is_empty());
+        let members = &self.members;
+        for species in &mut self.species {
+            let fitness = species
+                .members
+                .iter()
+                .map(|i| members[*i].fitness.unwrap_or(Scalar::NEG_INFINITY))
+                .collect::<Vec<_>>();
+            let best = species.members[ranking(&fitness)[0]];
+            species.representative = members[best].brain.clone();
+        }
+    }
+
+    /// Replaces members with next generation bred from evaluated ones.
+    pub fn evolve(&mut self) -> Result<()> {
+        if self.members.is_empty() {
+            return Err(Error::simple("Population is empty".to_owned()));
+        }
+        let mut fitness = Vec::with_capacity(self.members.len());
+        for (i, member) in self.members.iter().enumerate() {
+            match member.fitness {
+                Some(value) => fitness.push(value),
+                None => {
+                    return Err(Error::simple(format!(
+                        "Member {} of generation {} is not evaluated",
+                        i, self.generation
+                    )))
+                }
+            }
+        }
+
+        let size = self.members.len();
+        let mut species_of = vec![None; size];
+        for (i, species) in self.species.iter().enumerate() {
+            for member in &species.members {
+                species_of[*member] = Some(i);
+            }
+        }
+        // explicit fitness sharing.
+        let shared = if self.species.is_empty() {
+            fitness.clone()
+        } else {
+            let min = fitness.iter().cloned().fold(Scalar::INFINITY, Scalar::min);
+            fitness
+                .iter()
+                .zip(species_of.iter())
+                .map(|(f, s)| match s {
+                    Some(s) => (f - min) / self.species[*s].members.len() as Scalar,
+                    None => f - min,
+                })
+                .collect()
+        };
+        let mut members = ranking(&fitness)
+            .into_iter()
+            .take(self.elites)
+            .map(|i| Member::new(self.members[i].brain.clone()))
+            .collect::<Vec<_>>();
+        while members.len() < size {
+            let a = self.selection.select(&shared, &mut self.rng).unwrap();
+            let brain = if self.crossover > 0.0 && self.rng.gen::<Scalar>() < self.crossover {
+                let b = match species_of[a] {
+                    Some(s) => {
+                        let mates = &self.species[s].members;
+                        let mates_fitness = mates.iter().map(|i| fitness[*i]).collect::<Vec<_>>();
+                        mates[self
+                            .selection
+                            .select(&mates_fitness, &mut self.rng)
+                            .unwrap()]
+                    }
+                    None => self.selection.select(&shared, &mut self.rng).unwrap(),
+                };
+                // fitter parent goes first, so it keeps its structure in aligned crossover.
+                let (a, b) = if fitness[b] > fitness[a] {
+                    (b, a)
+                } else {
+                    (a, b)
+                };
+                self.offspring_builder.clone().build_merged_with_rng(
+                    &self.members[a].brain,
+                    &self.members[b].brain,
+                    &mut self.rng,
+                )
+            } else {
+                self.offspring_builder
+                    .clone()
+                    .build_mutated_with_rng(&self.members[a].brain, &mut self.rng)
+            };
+            members.push(Member::new(brain));
+        }
+        self.members = members;
+        self.generation += 1;
+        for species in &mut self.species {
+            species.members.clear();
+        }
+        Ok(())
+    }
+
+    /// Evaluates current generation and breeds the next one.
+    /// Returns stats of evaluated generation.
+    pub fn step<F>(&mut self, fitness: F) -> Result<GenerationStats>
+    where
+        F: FnMut(&Brain) -> Scalar,
+    {
+        let stats = self
+            .evaluate(fitness)
+            .ok_or_else(|| Error::simple("Population is empty".to_owned()))?;
+        self.evolve()?;
+        Ok(stats)
+    }
+}
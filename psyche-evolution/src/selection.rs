@@ -0,0 +1,70 @@
+use psyche_core::Scalar;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+
+/// Strategy of picking parents of next generation by their fitness.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Selection {
+    /// Picks the fittest of given number of randomly chosen members.
+    Tournament(usize),
+    /// Picks members with probability proportional to their fitness.
+    /// NOTE: Fitness is shifted so that the worst member has zero chance.
+    Roulette,
+    /// Picks randomly one of given number of the fittest members.
+    Elitist(usize),
+}
+
+impl Default for Selection {
+    fn default() -> Self {
+        Selection::Tournament(3)
+    }
+}
+
+impl Selection {
+    /// Returns index of selected member, or `None` when there are no members.
+    pub fn select<R>(&self, fitness: &[Scalar], rng: &mut R) -> Option<usize>
+    where
+        R: Rng,
+    {
+        if fitness.is_empty() {
+            return None;
+        }
+        match self {
+            Selection::Tournament(size) => (0..(*size).max(1))
+                .map(|_| rng.gen_range(0, fitness.len()))
+                .max_by(|a, b| compare(fitness[*a], fitness[*b])),
+            Selection::Roulette => {
+                let min = fitness.iter().cloned().fold(Scalar::INFINITY, Scalar::min);
+                let total = fitness.iter().map(|f| f - min).sum::<Scalar>();
+                if total <= 0.0 || !total.is_finite() {
+                    return Some(rng.gen_range(0, fitness.len()));
+                }
+                let mut value = rng.gen_range(0.0, total);
+                for (i, f) in fitness.iter().enumerate() {
+                    value -= f - min;
+                    if value < 0.0 {
+                        return Some(i);
+                    }
+                }
+                Some(fitness.len() - 1)
+            }
+            Selection::Elitist(count) => {
+                let ranking = ranking(fitness);
+                let count = (*count).max(1).min(ranking.len());
+                Some(ranking[rng.gen_range(0, count)])
+            }
+        }
+    }
+}
+
+/// Returns indices of members sorted from the fittest.
+pub fn ranking(fitness: &[Scalar]) -> Vec<usize> {
+    let mut result = (0..fitness.len()).collect::<Vec<_>>();
+    result.sort_by(|a, b| compare(fitness[*b], fitness[*a]));
+    result
+}
+
+fn compare(a: Scalar, b: Scalar) -> Ordering {
+    a.partial_cmp(&b).unwrap_or(Ordering::Equal)
+}
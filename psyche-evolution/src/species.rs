@@ -0,0 +1,49 @@
+use psyche_core::brain::Brain;
+use psyche_core::compatibility::Compatibility;
+use psyche_core::Scalar;
+use serde::{Deserialize, Serialize};
+
+/// Settings of splitting population into species of compatible brains.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Speciation {
+    pub compatibility: Compatibility,
+    /// Max compatibility distance of brain to species representative to join that species.
+    pub threshold: Scalar,
+}
+
+impl Default for Speciation {
+    fn default() -> Self {
+        Self {
+            compatibility: Default::default(),
+            threshold: 3.0,
+        }
+    }
+}
+
+/// Group of compatible members of population.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Species {
+    pub(crate) id: usize,
+    pub(crate) representative: Brain,
+    pub(crate) members: Vec<usize>,
+}
+
+impl Species {
+    #[inline]
+    pub fn id(&self) -> usize {
+        self.id
+    }
+
+    /// The fittest member of species when it was last evaluated.
+    #[inline]
+    pub fn representative(&self) -> &Brain {
+        &self.representative
+    }
+
+    /// Indices of population members; empty until current generation gets evaluated.
+    #[inline]
+    pub fn members(&self) -> &[usize] {
+        &self.members
+    }
+}
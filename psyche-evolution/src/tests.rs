@@ -0,0 +1,124 @@
+#![cfg(test)]
+use crate::population::*;
+use crate::selection::*;
+use crate::species::*;
+use psyche_core::brain::Brain;
+use psyche_core::brain_builder::*;
+use psyche_core::offspring_builder::*;
+use psyche_core::rng::*;
+use psyche_core::Scalar;
+
+fn make_population(seed: u64) -> Population {
+    let brain_builder = BrainBuilder::new()
+        .neurons(10)
+        .connections(20)
+        .sensors(2)
+        .effectors(2);
+    let offspring_builder = OffspringBuilder::new()
+        .new_neurons(2)
+        .new_connections(4)
+        .new_sensors(0)
+        .new_effectors(0);
+    Population::from_builder_with_rng(&brain_builder, 8, &mut BrainRng::from_u64(seed))
+        .selection(Selection::Tournament(3))
+        .elites(2)
+        .crossover(0.5)
+        .offspring_builder(offspring_builder)
+}
+
+fn fitness(brain: &Brain) -> Scalar {
+    brain.synapses().len() as Scalar
+}
+
+#[test]
+fn test_selection() {
+    let fitness = vec![1.0, 5.0, -2.0, 3.0];
+    assert_eq!(ranking(&fitness), vec![1, 3, 0, 2]);
+    let mut rng = BrainRng::from_u64(42);
+    assert_eq!(Selection::Roulette.select(&[], &mut rng), None);
+    for _ in 0..100 {
+        assert_eq!(Selection::Elitist(1).select(&fitness, &mut rng), Some(1));
+        assert_ne!(Selection::Roulette.select(&fitness, &mut rng), Some(2));
+        let index = Selection::Elitist(2).select(&fitness, &mut rng).unwrap();
+        assert!(index == 1 || index == 3);
+        let index = Selection::Tournament(3).select(&fitness, &mut rng).unwrap();
+        assert!(index < fitness.len());
+    }
+    assert_eq!(
+        Selection::Tournament(100).select(&fitness, &mut rng),
+        Some(1)
+    );
+}
+
+#[test]
+fn test_population() {
+    let mut population = make_population(42);
+    assert_eq!(population.members().len(), 8);
+    assert!(population.best().is_none());
+    assert!(population.stats().is_none());
+    assert!(population.evolve().is_err());
+
+    let mut best = Scalar::NEG_INFINITY;
+    for i in 0..5 {
+        let stats = population.step(fitness).unwrap();
+        assert_eq!(stats.generation, i);
+        assert!(stats.worst <= stats.mean && stats.mean <= stats.best);
+        // elites keep the best fitness from decreasing.
+        assert!(stats.best >= best);
+        best = stats.best;
+        assert_eq!(population.members().len(), 8);
+    }
+    assert_eq!(population.generation(), 5);
+    assert_eq!(population.history().len(), 5);
+    assert!(population.best().is_none());
+    let stats = population.evaluate(fitness).unwrap();
+    assert_eq!(population.history().len(), 6);
+    assert_eq!(population.best().unwrap().fitness, Some(stats.best));
+
+    assert!(Population::new(vec![]).step(fitness).is_err());
+}
+
+#[test]
+fn test_population_deterministic() {
+    let mut a = make_population(42).seed(7);
+    let mut b = make_population(42).seed(7);
+    for _ in 0..3 {
+        assert_eq!(a.step(fitness).unwrap(), b.step(fitness).unwrap());
+    }
+    assert_eq!(a.members(), b.members());
+}
+
+#[test]
+fn test_speciation() {
+    let speciation = Speciation {
+        threshold: 0.5,
+        ..Default::default()
+    };
+    let mut population = make_population(42)
+        .offspring_builder(
+            OffspringBuilder::new()
+                .new_neurons(1)
+                .new_connections(2)
+                .new_sensors(0)
+                .new_effectors(0)
+                .homology_range(Some(1.0)),
+        )
+        .speciation(Some(speciation));
+    for _ in 0..3 {
+        let stats = population.evaluate(fitness).unwrap();
+        assert!(stats.species > 0);
+        assert_eq!(stats.species, population.species().len());
+        let mut members = population
+            .species()
+            .iter()
+            .flat_map(|s| s.members().iter().cloned())
+            .collect::<Vec<_>>();
+        members.sort();
+        assert_eq!(members, (0..8).collect::<Vec<_>>());
+        population.evolve().unwrap();
+        assert!(population.species().iter().all(|s| s.members().is_empty()));
+    }
+    for member in population.members() {
+        member.brain.validate().unwrap();
+    }
+}
@@ -0,0 +1,214 @@
+use crate::color::Color;
+use crate::font;
+use crate::render::{Config, Frame};
+use png::{BitDepth, ColorType, Encoder};
+use psyche_core::brain::BrainActivityMap;
+use psyche_core::error::*;
+use psyche_core::Scalar;
+
+const TEXT_SCALE: usize = 2;
+
+/// RGB image.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bitmap {
+    width: usize,
+    height: usize,
+    pixels: Vec<u8>,
+}
+
+impl Bitmap {
+    pub fn new(width: usize, height: usize, color: Color) -> Self {
+        let mut pixels = Vec::with_capacity(width * height * 3);
+        for _ in 0..(width * height) {
+            pixels.extend_from_slice(&[color.0, color.1, color.2]);
+        }
+        Self {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    #[inline]
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    #[inline]
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Row-major RGB pixels.
+    #[inline]
+    pub fn pixels(&self) -> &[u8] {
+        &self.pixels
+    }
+
+    pub fn pixel(&self, x: usize, y: usize) -> Option<Color> {
+        if x < self.width && y < self.height {
+            let i = (y * self.width + x) * 3;
+            Some(Color(
+                self.pixels[i],
+                self.pixels[i + 1],
+                self.pixels[i + 2],
+            ))
+        } else {
+            None
+        }
+    }
+
+    pub fn set_pixel(&mut self, x: isize, y: isize, color: Color) {
+        if x >= 0 && y >= 0 && (x as usize) < self.width && (y as usize) < self.height {
+            let i = (y as usize * self.width + x as usize) * 3;
+            self.pixels[i] = color.0;
+            self.pixels[i + 1] = color.1;
+            self.pixels[i + 2] = color.2;
+        }
+    }
+
+    pub fn draw_line(&mut self, from: (Scalar, Scalar), to: (Scalar, Scalar), color: Color) {
+        let (from, to) = match self.clip_line(from, to) {
+            Some(line) => line,
+            None => return,
+        };
+        let dx = to.0 - from.0;
+        let dy = to.1 - from.1;
+        let steps = dx.abs().max(dy.abs()).ceil().max(1.0);
+        for i in 0..=(steps as usize) {
+            let factor = i as Scalar / steps;
+            self.set_pixel(
+                (from.0 + dx * factor).round() as isize,
+                (from.1 + dy * factor).round() as isize,
+                color,
+            );
+        }
+    }
+
+    // Liang-Barsky clipping of line to image bounds.
+    fn clip_line(
+        &self,
+        from: (Scalar, Scalar),
+        to: (Scalar, Scalar),
+    ) -> Option<((Scalar, Scalar), (Scalar, Scalar))> {
+        let dx = to.0 - from.0;
+        let dy = to.1 - from.1;
+        let mut start: Scalar = 0.0;
+        let mut end: Scalar = 1.0;
+        for (p, q) in &[
+            (-dx, from.0 + 1.0),
+            (dx, self.width as Scalar - from.0),
+            (-dy, from.1 + 1.0),
+            (dy, self.height as Scalar - from.1),
+        ] {
+            if *p == 0.0 {
+                if *q < 0.0 {
+                    return None;
+                }
+            } else {
+                let t = q / p;
+                if *p < 0.0 {
+                    start = start.max(t);
+                } else {
+                    end = end.min(t);
+                }
+            }
+        }
+        if start > end {
+            return None;
+        }
+        Some((
+            (from.0 + dx * start, from.1 + dy * start),
+            (from.0 + dx * end, from.1 + dy * end),
+        ))
+    }
+
+    pub fn fill_circle(&mut self, x: Scalar, y: Scalar, radius: Scalar, color: Color) {
+        let radius_sqr = radius * radius;
+        let min_x = (x - radius).floor().max(0.0) as isize;
+        let min_y = (y - radius).floor().max(0.0) as isize;
+        let max_x = (x + radius).ceil().min(self.width as Scalar) as isize;
+        let max_y = (y + radius).ceil().min(self.height as Scalar) as isize;
+        for py in min_y..max_y {
+            for px in min_x..max_x {
+                let dx = px as Scalar + 0.5 - x;
+                let dy = py as Scalar + 0.5 - y;
+                if dx * dx + dy * dy <= radius_sqr {
+                    self.set_pixel(px, py, color);
+                }
+            }
+        }
+    }
+
+    /// Draws text with built-in pixel font (letters are drawn uppercase).
+    pub fn draw_text(&mut self, x: isize, y: isize, text: &str, scale: usize, color: Color) {
+        for (i, c) in text.chars().enumerate() {
+            let glyph = font::glyph(c);
+            let left = x + (i * font::ADVANCE * scale) as isize;
+            for (row, bits) in glyph.iter().enumerate() {
+                for column in 0..font::WIDTH {
+                    if bits & (1 << (font::WIDTH - 1 - column)) != 0 {
+                        for sy in 0..scale {
+                            for sx in 0..scale {
+                                self.set_pixel(
+                                    left + (column * scale + sx) as isize,
+                                    y + (row * scale + sy) as isize,
+                                    color,
+                                );
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn to_png(&self) -> Result<Vec<u8>> {
+        let mut result = vec![];
+        {
+            let mut encoder = Encoder::new(&mut result, self.width as u32, self.height as u32);
+            encoder.set_color(ColorType::RGB);
+            encoder.set_depth(BitDepth::Eight);
+            let mut writer = encoder
+                .write_header()
+                .map_err(|e| Error::simple(format!("{}", e)))?;
+            writer
+                .write_image_data(&self.pixels)
+                .map_err(|e| Error::simple(format!("{}", e)))?;
+        }
+        Ok(result)
+    }
+}
+
+/// renders activity map projected onto image plane into bitmap.
+/// NOTE: Each of `overlay` lines is rendered as text in top left corner.
+pub fn render(activity_map: &BrainActivityMap, config: &Config, overlay: &[String]) -> Bitmap {
+    let frame = Frame::new(activity_map, config);
+    let mut bitmap = Bitmap::new(config.width, config.height, config.background);
+    for segment in &frame.segments {
+        bitmap.draw_line(segment.from, segment.to, segment.color);
+    }
+    for circle in &frame.circles {
+        bitmap.fill_circle(circle.x, circle.y, circle.radius, circle.color);
+    }
+    for (i, line) in overlay.iter().enumerate() {
+        bitmap.draw_text(
+            8,
+            (8 + i * (font::HEIGHT + 2) * TEXT_SCALE) as isize,
+            line,
+            TEXT_SCALE,
+            config.text,
+        );
+    }
+    bitmap
+}
+
+/// generates PNG image bytes of activity map projected onto image plane.
+/// NOTE: Each of `overlay` lines is rendered as text in top left corner.
+pub fn generate(
+    activity_map: &BrainActivityMap,
+    config: &Config,
+    overlay: &[String],
+) -> Result<Vec<u8>> {
+    render(activity_map, config, overlay).to_png()
+}
@@ -0,0 +1,154 @@
+use psyche_core::Scalar;
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+
+const VIRIDIS: [(Scalar, Color); 9] = [
+    (0.0, Color(68, 1, 84)),
+    (0.125, Color(71, 44, 122)),
+    (0.25, Color(59, 81, 139)),
+    (0.375, Color(44, 113, 142)),
+    (0.5, Color(33, 144, 141)),
+    (0.625, Color(39, 173, 129)),
+    (0.75, Color(92, 200, 99)),
+    (0.875, Color(170, 220, 50)),
+    (1.0, Color(253, 231, 37)),
+];
+
+const HEAT: [(Scalar, Color); 4] = [
+    (0.0, Color(0, 0, 0)),
+    (1.0 / 3.0, Color(255, 0, 0)),
+    (2.0 / 3.0, Color(255, 255, 0)),
+    (1.0, Color(255, 255, 255)),
+];
+
+/// (R, G, B)
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Color(pub u8, pub u8, pub u8);
+
+impl Color {
+    pub fn lerp(self, other: Self, factor: Scalar) -> Self {
+        let factor = factor.clamp(0.0, 1.0);
+        let channel = |a: u8, b: u8| {
+            (Scalar::from(a) + (Scalar::from(b) - Scalar::from(a)) * factor).round() as u8
+        };
+        Self(
+            channel(self.0, other.0),
+            channel(self.1, other.1),
+            channel(self.2, other.2),
+        )
+    }
+
+    /// Color channels converted from sRGB into linear space (0..1).
+    pub fn to_linear(self) -> [f32; 3] {
+        let channel = |c: u8| {
+            let c = f32::from(c) / 255.0;
+            if c <= 0.040_45 {
+                c / 12.92
+            } else {
+                ((c + 0.055) / 1.055).powf(2.4)
+            }
+        };
+        [channel(self.0), channel(self.1), channel(self.2)]
+    }
+}
+
+impl From<(u8, u8, u8)> for Color {
+    fn from(value: (u8, u8, u8)) -> Self {
+        Self(value.0, value.1, value.2)
+    }
+}
+
+impl From<[u8; 3]> for Color {
+    fn from(value: [u8; 3]) -> Self {
+        Self(value[0], value[1], value[2])
+    }
+}
+
+/// Maps factor (0..1) onto color.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Colormap {
+    /// Same color for every factor.
+    Solid(Color),
+    Viridis,
+    /// Black, red, yellow, white.
+    Heat,
+    /// (factor, color) stops sorted by factor, colors between them are interpolated.
+    Stops(Vec<(Scalar, Color)>),
+}
+
+impl Colormap {
+    /// Colormap going from one color to another.
+    pub fn linear(from: Color, to: Color) -> Self {
+        Colormap::Stops(vec![(0.0, from), (1.0, to)])
+    }
+
+    pub fn sample(&self, factor: Scalar) -> Color {
+        match self {
+            Colormap::Solid(color) => *color,
+            Colormap::Viridis => sample_stops(&VIRIDIS, factor),
+            Colormap::Heat => sample_stops(&HEAT, factor),
+            Colormap::Stops(stops) => sample_stops(stops, factor),
+        }
+    }
+}
+
+impl From<Color> for Colormap {
+    fn from(value: Color) -> Self {
+        Colormap::Solid(value)
+    }
+}
+
+/// Maps values from range onto colormap.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Gradient {
+    pub colormap: Colormap,
+    pub range: Range<Scalar>,
+}
+
+impl Gradient {
+    pub fn new(colormap: Colormap, range: Range<Scalar>) -> Self {
+        Self { colormap, range }
+    }
+
+    /// Position (0..1) of value within gradient range.
+    pub fn factor(&self, value: Scalar) -> Scalar {
+        let size = self.range.end - self.range.start;
+        if size.abs() > 0.0 {
+            ((value - self.range.start) / size).clamp(0.0, 1.0)
+        } else {
+            1.0
+        }
+    }
+
+    pub fn color(&self, value: Scalar) -> Color {
+        self.colormap.sample(self.factor(value))
+    }
+}
+
+impl From<Color> for Gradient {
+    fn from(value: Color) -> Self {
+        Self::new(value.into(), 0.0..1.0)
+    }
+}
+
+fn sample_stops(stops: &[(Scalar, Color)], factor: Scalar) -> Color {
+    let first = match stops.first() {
+        Some(first) => first,
+        None => return Color(0, 0, 0),
+    };
+    if factor <= first.0 {
+        return first.1;
+    }
+    for pair in stops.windows(2) {
+        let (from, to) = (pair[0], pair[1]);
+        if factor <= to.0 {
+            let size = to.0 - from.0;
+            return if size > 0.0 {
+                from.1.lerp(to.1, (factor - from.0) / size)
+            } else {
+                to.1
+            };
+        }
+    }
+    stops[stops.len() - 1].1
+}
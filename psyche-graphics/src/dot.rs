@@ -0,0 +1,53 @@
+use crate::into_string;
+use crate::network::*;
+use psyche_core::brain::Brain;
+use psyche_core::error::*;
+use std::io::Write;
+
+/// generates Graphviz DOT string of neurons (nodes) and synapses (edges).
+/// NOTE: Neuron X and Y coordinates are stored as pinned node positions.
+pub fn generate_string(brain: &Brain) -> Result<String> {
+    into_string(generate(brain)?)
+}
+
+/// generates Graphviz DOT bytes of neurons (nodes) and synapses (edges).
+/// NOTE: Neuron X and Y coordinates are stored as pinned node positions.
+pub fn generate(brain: &Brain) -> Result<Vec<u8>> {
+    let mut result = vec![];
+    write(brain, &mut result)?;
+    Ok(result)
+}
+
+/// writes Graphviz DOT of neurons (nodes) and synapses (edges).
+/// NOTE: Neuron X and Y coordinates are stored as pinned node positions.
+pub fn write<W>(brain: &Brain, mut writer: W) -> Result<()>
+where
+    W: Write,
+{
+    let network = Network::new(brain);
+    writeln!(writer, r#"digraph "{}" {{"#, network.id)?;
+    for node in &network.nodes {
+        writeln!(
+            writer,
+            r#"  "{}" [pos="{},{}!", z="{}", potential="{}", inhibitory="{}", sensor="{}", effector="{}", lineage="{}"];"#,
+            node.id,
+            node.position.x,
+            node.position.y,
+            node.position.z,
+            node.potential,
+            node.inhibitory,
+            node.sensor,
+            node.effector,
+            node.lineage
+        )?;
+    }
+    for edge in &network.edges {
+        writeln!(
+            writer,
+            r#"  "{}" -> "{}" [receptors="{}", distance="{}", inhibitory="{}", lineage="{}"];"#,
+            edge.source, edge.target, edge.receptors, edge.distance, edge.inhibitory, edge.lineage
+        )?;
+    }
+    writeln!(writer, "}}")?;
+    Ok(())
+}
@@ -0,0 +1,113 @@
+use crate::into_string;
+use crate::network::*;
+use psyche_core::brain::Brain;
+use psyche_core::error::*;
+use std::io::Write;
+
+/// generates GEXF string of neurons (nodes) and synapses (edges).
+/// NOTE: Neuron positions are stored as visualization positions.
+pub fn generate_string(brain: &Brain) -> Result<String> {
+    into_string(generate(brain)?)
+}
+
+/// generates GEXF bytes of neurons (nodes) and synapses (edges).
+/// NOTE: Neuron positions are stored as visualization positions.
+pub fn generate(brain: &Brain) -> Result<Vec<u8>> {
+    let mut result = vec![];
+    write(brain, &mut result)?;
+    Ok(result)
+}
+
+/// writes GEXF of neurons (nodes) and synapses (edges).
+/// NOTE: Neuron positions are stored as visualization positions.
+pub fn write<W>(brain: &Brain, mut writer: W) -> Result<()>
+where
+    W: Write,
+{
+    let network = Network::new(brain);
+    writeln!(writer, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(
+        writer,
+        r#"<gexf xmlns="http://www.gexf.net/1.2draft" xmlns:viz="http://www.gexf.net/1.2draft/viz" version="1.2">"#
+    )?;
+    writeln!(
+        writer,
+        r#"  <graph id="{}" mode="static" defaultedgetype="directed">"#,
+        network.id
+    )?;
+    writeln!(writer, r#"    <attributes class="node">"#)?;
+    write_attribute(&mut writer, 0, "potential", "double")?;
+    write_attribute(&mut writer, 1, "inhibitory", "boolean")?;
+    write_attribute(&mut writer, 2, "sensor", "boolean")?;
+    write_attribute(&mut writer, 3, "effector", "boolean")?;
+    write_attribute(&mut writer, 4, "lineage", "string")?;
+    writeln!(writer, "    </attributes>")?;
+    writeln!(writer, r#"    <attributes class="edge">"#)?;
+    write_attribute(&mut writer, 0, "receptors", "double")?;
+    write_attribute(&mut writer, 1, "distance", "double")?;
+    write_attribute(&mut writer, 2, "inhibitory", "boolean")?;
+    write_attribute(&mut writer, 3, "lineage", "string")?;
+    writeln!(writer, "    </attributes>")?;
+    writeln!(writer, "    <nodes>")?;
+    for node in &network.nodes {
+        writeln!(writer, r#"      <node id="{}">"#, node.id)?;
+        writeln!(writer, "        <attvalues>")?;
+        write_value(&mut writer, 0, node.potential)?;
+        write_value(&mut writer, 1, node.inhibitory)?;
+        write_value(&mut writer, 2, node.sensor)?;
+        write_value(&mut writer, 3, node.effector)?;
+        write_value(&mut writer, 4, &node.lineage)?;
+        writeln!(writer, "        </attvalues>")?;
+        writeln!(
+            writer,
+            r#"        <viz:position x="{}" y="{}" z="{}"/>"#,
+            node.position.x, node.position.y, node.position.z
+        )?;
+        writeln!(writer, "      </node>")?;
+    }
+    writeln!(writer, "    </nodes>")?;
+    writeln!(writer, "    <edges>")?;
+    for (i, edge) in network.edges.iter().enumerate() {
+        writeln!(
+            writer,
+            r#"      <edge id="{}" source="{}" target="{}" weight="{}">"#,
+            i, edge.source, edge.target, edge.receptors
+        )?;
+        writeln!(writer, "        <attvalues>")?;
+        write_value(&mut writer, 0, edge.receptors)?;
+        write_value(&mut writer, 1, edge.distance)?;
+        write_value(&mut writer, 2, edge.inhibitory)?;
+        write_value(&mut writer, 3, &edge.lineage)?;
+        writeln!(writer, "        </attvalues>")?;
+        writeln!(writer, "      </edge>")?;
+    }
+    writeln!(writer, "    </edges>")?;
+    writeln!(writer, "  </graph>")?;
+    writeln!(writer, "</gexf>")?;
+    Ok(())
+}
+
+fn write_attribute<W>(writer: &mut W, id: usize, title: &str, ty: &str) -> Result<()>
+where
+    W: Write,
+{
+    writeln!(
+        writer,
+        r#"      <attribute id="{}" title="{}" type="{}"/>"#,
+        id, title, ty
+    )?;
+    Ok(())
+}
+
+fn write_value<W, T>(writer: &mut W, id: usize, value: T) -> Result<()>
+where
+    W: Write,
+    T: std::fmt::Display,
+{
+    writeln!(
+        writer,
+        r#"          <attvalue for="{}" value="{}"/>"#,
+        id, value
+    )?;
+    Ok(())
+}
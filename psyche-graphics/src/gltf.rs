@@ -0,0 +1,329 @@
+use crate::scene::{Config, Scene};
+use psyche_core::brain::BrainActivityMap;
+use psyche_core::error::*;
+use psyche_core::Scalar;
+
+const ARRAY_BUFFER: usize = 34962;
+const ELEMENT_ARRAY_BUFFER: usize = 34963;
+const FLOAT: usize = 5126;
+const UNSIGNED_INT: usize = 5125;
+const MODE_LINES: usize = 1;
+const MODE_TRIANGLES: usize = 4;
+
+// octahedron glyph used for points, so they can be scaled.
+const GLYPH_VERTICES: [[f32; 3]; 6] = [
+    [1.0, 0.0, 0.0],
+    [-1.0, 0.0, 0.0],
+    [0.0, 1.0, 0.0],
+    [0.0, -1.0, 0.0],
+    [0.0, 0.0, 1.0],
+    [0.0, 0.0, -1.0],
+];
+const GLYPH_INDICES: [u32; 24] = [
+    0, 2, 4, 2, 1, 4, 1, 3, 4, 3, 0, 4, 2, 0, 5, 1, 2, 5, 3, 1, 5, 0, 3, 5,
+];
+
+/// generates binary glTF 2.0 (GLB) bytes from activity map.
+/// NOTE: Points are stored as octahedrons scaled by their potential; colors use unlit material.
+pub fn generate(activity_map: &BrainActivityMap, config: &Config) -> Result<Vec<u8>> {
+    let mut animation = Animation::new(config.clone());
+    animation.push(0.0, activity_map);
+    animation.generate()
+}
+
+/// Collects activity maps of simulation run into single animated binary glTF 2.0 (GLB) file.
+/// Each frame is separate mesh, shown only since its time until time of next frame.
+#[derive(Debug, Clone, Default)]
+pub struct Animation {
+    config: Config,
+    times: Vec<Scalar>,
+    maps: Vec<BrainActivityMap>,
+}
+
+impl Animation {
+    pub fn new(config: Config) -> Self {
+        Self {
+            config,
+            times: vec![],
+            maps: vec![],
+        }
+    }
+
+    #[inline]
+    pub fn frames_count(&self) -> usize {
+        self.times.len()
+    }
+
+    /// Adds frame shown since given time (times must increase).
+    pub fn push(&mut self, time: Scalar, activity_map: &BrainActivityMap) {
+        self.times.push(time);
+        self.maps.push(activity_map.clone());
+    }
+
+    pub fn clear(&mut self) {
+        self.times.clear();
+        self.maps.clear();
+    }
+
+    /// generates binary glTF 2.0 (GLB) bytes of all frames.
+    pub fn generate(&self) -> Result<Vec<u8>> {
+        if self.times.windows(2).any(|w| w[1] <= w[0]) {
+            return Err(Error::simple(
+                "Animation frames times are not increasing".to_owned(),
+            ));
+        }
+        let start = self.times.first().cloned().unwrap_or(0.0);
+        let times = self
+            .times
+            .iter()
+            .map(|t| (t - start) as f32)
+            .collect::<Vec<_>>();
+        let mut builder = Builder::default();
+        let mut nodes = vec![];
+        let mut meshes = vec![];
+        for (i, map) in self.maps.iter().enumerate() {
+            let primitives = builder.push_scene(&Scene::new(map, &self.config));
+            let scale = if i == 0 { "" } else { r#","scale":[0,0,0]"# };
+            if primitives.is_empty() {
+                nodes.push(format!(r#"{{"name":"frame-{}"{}}}"#, i, scale));
+            } else {
+                nodes.push(format!(
+                    r#"{{"name":"frame-{}","mesh":{}{}}}"#,
+                    i,
+                    meshes.len(),
+                    scale
+                ));
+                meshes.push(format!(r#"{{"primitives":[{}]}}"#, primitives.join(",")));
+            }
+        }
+        let mut samplers = vec![];
+        let mut channels = vec![];
+        if times.len() > 1 {
+            for (i, time) in times.iter().enumerate() {
+                let mut keys = vec![];
+                if i > 0 {
+                    keys.push((0.0, 0.0));
+                }
+                keys.push((*time, 1.0));
+                if let Some(next) = times.get(i + 1) {
+                    keys.push((*next, 0.0));
+                }
+                let input = builder.push_scalars(&keys.iter().map(|k| k.0).collect::<Vec<_>>());
+                let output = builder.push_vec3(
+                    &keys.iter().map(|k| [k.1; 3]).collect::<Vec<_>>(),
+                    None,
+                    false,
+                );
+                channels.push(format!(
+                    r#"{{"sampler":{},"target":{{"node":{},"path":"scale"}}}}"#,
+                    samplers.len(),
+                    i
+                ));
+                samplers.push(format!(
+                    r#"{{"input":{},"output":{},"interpolation":"STEP"}}"#,
+                    input, output
+                ));
+            }
+        }
+
+        let mut json = String::new();
+        json.push_str(r#"{"asset":{"version":"2.0","generator":"psyche-graphics"}"#);
+        json.push_str(r#","extensionsUsed":["KHR_materials_unlit"]"#);
+        json.push_str(&format!(
+            r#","scene":0,"scenes":[{{"nodes":[{}]}}]"#,
+            (0..nodes.len())
+                .map(|i| i.to_string())
+                .collect::<Vec<_>>()
+                .join(",")
+        ));
+        push_json_array(&mut json, "nodes", &nodes);
+        push_json_array(&mut json, "meshes", &meshes);
+        json.push_str(
+            r#","materials":[{"pbrMetallicRoughness":{"metallicFactor":0},"extensions":{"KHR_materials_unlit":{}}}]"#,
+        );
+        push_json_array(&mut json, "accessors", &builder.accessors);
+        push_json_array(&mut json, "bufferViews", &builder.views);
+        if !builder.buffer.is_empty() {
+            json.push_str(&format!(
+                r#","buffers":[{{"byteLength":{}}}]"#,
+                builder.buffer.len()
+            ));
+        }
+        if !channels.is_empty() {
+            json.push_str(&format!(
+                r#","animations":[{{"name":"activity","channels":[{}],"samplers":[{}]}}]"#,
+                channels.join(","),
+                samplers.join(",")
+            ));
+        }
+        json.push('}');
+        Ok(make_glb(json.into_bytes(), builder.buffer))
+    }
+}
+
+#[derive(Default)]
+struct Builder {
+    buffer: Vec<u8>,
+    views: Vec<String>,
+    accessors: Vec<String>,
+}
+
+impl Builder {
+    // returns primitives JSON.
+    fn push_scene(&mut self, scene: &Scene) -> Vec<String> {
+        let mut primitives = vec![];
+        if !scene.points.is_empty() {
+            let mut positions = Vec::with_capacity(scene.points.len() * GLYPH_VERTICES.len());
+            let mut colors = Vec::with_capacity(positions.capacity());
+            let mut indices = Vec::with_capacity(scene.points.len() * GLYPH_INDICES.len());
+            for point in &scene.points {
+                let offset = positions.len() as u32;
+                let radius = point.size as f32 * 0.5;
+                let color = point.color.to_linear();
+                for v in &GLYPH_VERTICES {
+                    positions.push([
+                        point.position.x as f32 + v[0] * radius,
+                        point.position.y as f32 + v[1] * radius,
+                        point.position.z as f32 + v[2] * radius,
+                    ]);
+                    colors.push(color);
+                }
+                indices.extend(GLYPH_INDICES.iter().map(|i| i + offset));
+            }
+            let position = self.push_vec3(&positions, Some(ARRAY_BUFFER), true);
+            let color = self.push_vec3(&colors, Some(ARRAY_BUFFER), false);
+            let indices = self.push_indices(&indices);
+            primitives.push(format!(
+                r#"{{"attributes":{{"POSITION":{},"COLOR_0":{}}},"indices":{},"material":0,"mode":{}}}"#,
+                position, color, indices, MODE_TRIANGLES
+            ));
+        }
+        if !scene.lines.is_empty() {
+            let mut positions = Vec::with_capacity(scene.lines.len() * 2);
+            let mut colors = Vec::with_capacity(positions.capacity());
+            for line in &scene.lines {
+                let color = line.color.to_linear();
+                for p in &[line.from, line.to] {
+                    positions.push([p.x as f32, p.y as f32, p.z as f32]);
+                    colors.push(color);
+                }
+            }
+            let position = self.push_vec3(&positions, Some(ARRAY_BUFFER), true);
+            let color = self.push_vec3(&colors, Some(ARRAY_BUFFER), false);
+            primitives.push(format!(
+                r#"{{"attributes":{{"POSITION":{},"COLOR_0":{}}},"material":0,"mode":{}}}"#,
+                position, color, MODE_LINES
+            ));
+        }
+        primitives
+    }
+
+    // returns accessor index.
+    fn push_vec3(&mut self, values: &[[f32; 3]], target: Option<usize>, bounds: bool) -> usize {
+        let view = self.push_view(
+            values
+                .iter()
+                .flat_map(|v| v.iter())
+                .flat_map(|v| v.to_le_bytes().to_vec()),
+            target,
+        );
+        let bounds = if bounds {
+            let mut min = [f32::INFINITY; 3];
+            let mut max = [f32::NEG_INFINITY; 3];
+            for v in values {
+                for i in 0..3 {
+                    min[i] = min[i].min(v[i]);
+                    max[i] = max[i].max(v[i]);
+                }
+            }
+            format!(
+                r#","min":[{},{},{}],"max":[{},{},{}]"#,
+                min[0], min[1], min[2], max[0], max[1], max[2]
+            )
+        } else {
+            String::new()
+        };
+        self.push_accessor(view, FLOAT, values.len(), "VEC3", &bounds)
+    }
+
+    // returns accessor index.
+    fn push_scalars(&mut self, values: &[f32]) -> usize {
+        let view = self.push_view(values.iter().flat_map(|v| v.to_le_bytes().to_vec()), None);
+        let min = values.iter().cloned().fold(f32::INFINITY, f32::min);
+        let max = values.iter().cloned().fold(f32::NEG_INFINITY, f32::max);
+        let bounds = format!(r#","min":[{}],"max":[{}]"#, min, max);
+        self.push_accessor(view, FLOAT, values.len(), "SCALAR", &bounds)
+    }
+
+    // returns accessor index.
+    fn push_indices(&mut self, values: &[u32]) -> usize {
+        let view = self.push_view(
+            values.iter().flat_map(|v| v.to_le_bytes().to_vec()),
+            Some(ELEMENT_ARRAY_BUFFER),
+        );
+        self.push_accessor(view, UNSIGNED_INT, values.len(), "SCALAR", "")
+    }
+
+    fn push_accessor(
+        &mut self,
+        view: usize,
+        component_type: usize,
+        count: usize,
+        type_: &str,
+        bounds: &str,
+    ) -> usize {
+        self.accessors.push(format!(
+            r#"{{"bufferView":{},"componentType":{},"count":{},"type":"{}"{}}}"#,
+            view, component_type, count, type_, bounds
+        ));
+        self.accessors.len() - 1
+    }
+
+    // returns buffer view index.
+    fn push_view<I>(&mut self, bytes: I, target: Option<usize>) -> usize
+    where
+        I: Iterator<Item = u8>,
+    {
+        let offset = self.buffer.len();
+        self.buffer.extend(bytes);
+        let length = self.buffer.len() - offset;
+        let target = match target {
+            Some(target) => format!(r#","target":{}"#, target),
+            None => String::new(),
+        };
+        self.views.push(format!(
+            r#"{{"buffer":0,"byteOffset":{},"byteLength":{}{}}}"#,
+            offset, length, target
+        ));
+        self.views.len() - 1
+    }
+}
+
+fn push_json_array(json: &mut String, name: &str, items: &[String]) {
+    if !items.is_empty() {
+        json.push_str(&format!(r#","{}":[{}]"#, name, items.join(",")));
+    }
+}
+
+fn make_glb(mut json: Vec<u8>, mut buffer: Vec<u8>) -> Vec<u8> {
+    // chunks are aligned to 4 bytes.
+    json.resize((json.len() + 3) & !3, b' ');
+    buffer.resize((buffer.len() + 3) & !3, 0);
+    let mut length = 12 + 8 + json.len();
+    if !buffer.is_empty() {
+        length += 8 + buffer.len();
+    }
+    let mut result = Vec::with_capacity(length);
+    result.extend_from_slice(b"glTF");
+    result.extend_from_slice(&2u32.to_le_bytes());
+    result.extend_from_slice(&(length as u32).to_le_bytes());
+    result.extend_from_slice(&(json.len() as u32).to_le_bytes());
+    result.extend_from_slice(b"JSON");
+    result.extend_from_slice(&json);
+    if !buffer.is_empty() {
+        result.extend_from_slice(&(buffer.len() as u32).to_le_bytes());
+        result.extend_from_slice(b"BIN\0");
+        result.extend_from_slice(&buffer);
+    }
+    result
+}
@@ -0,0 +1,91 @@
+use crate::into_string;
+use crate::network::*;
+use psyche_core::brain::Brain;
+use psyche_core::error::*;
+use std::io::Write;
+
+/// generates GraphML string of neurons (nodes) and synapses (edges).
+pub fn generate_string(brain: &Brain) -> Result<String> {
+    into_string(generate(brain)?)
+}
+
+/// generates GraphML bytes of neurons (nodes) and synapses (edges).
+pub fn generate(brain: &Brain) -> Result<Vec<u8>> {
+    let mut result = vec![];
+    write(brain, &mut result)?;
+    Ok(result)
+}
+
+/// writes GraphML of neurons (nodes) and synapses (edges).
+pub fn write<W>(brain: &Brain, mut writer: W) -> Result<()>
+where
+    W: Write,
+{
+    let network = Network::new(brain);
+    writeln!(writer, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(
+        writer,
+        r#"<graphml xmlns="http://graphml.graphdrawing.org/xmlns">"#
+    )?;
+    for (id, name, kind, ty) in &[
+        ("lineage", "lineage", "node", "string"),
+        ("x", "x", "node", "double"),
+        ("y", "y", "node", "double"),
+        ("z", "z", "node", "double"),
+        ("potential", "potential", "node", "double"),
+        ("inhibitory", "inhibitory", "node", "boolean"),
+        ("sensor", "sensor", "node", "boolean"),
+        ("effector", "effector", "node", "boolean"),
+        ("synapse_lineage", "lineage", "edge", "string"),
+        ("receptors", "receptors", "edge", "double"),
+        ("distance", "distance", "edge", "double"),
+        ("synapse_inhibitory", "inhibitory", "edge", "boolean"),
+    ] {
+        writeln!(
+            writer,
+            r#"  <key id="{}" for="{}" attr.name="{}" attr.type="{}"/>"#,
+            id, kind, name, ty
+        )?;
+    }
+    writeln!(
+        writer,
+        r#"  <graph id="{}" edgedefault="directed">"#,
+        network.id
+    )?;
+    for node in &network.nodes {
+        writeln!(writer, r#"    <node id="{}">"#, node.id)?;
+        write_data(&mut writer, "lineage", &node.lineage)?;
+        write_data(&mut writer, "x", node.position.x)?;
+        write_data(&mut writer, "y", node.position.y)?;
+        write_data(&mut writer, "z", node.position.z)?;
+        write_data(&mut writer, "potential", node.potential)?;
+        write_data(&mut writer, "inhibitory", node.inhibitory)?;
+        write_data(&mut writer, "sensor", node.sensor)?;
+        write_data(&mut writer, "effector", node.effector)?;
+        writeln!(writer, "    </node>")?;
+    }
+    for edge in &network.edges {
+        writeln!(
+            writer,
+            r#"    <edge source="{}" target="{}">"#,
+            edge.source, edge.target
+        )?;
+        write_data(&mut writer, "synapse_lineage", &edge.lineage)?;
+        write_data(&mut writer, "receptors", edge.receptors)?;
+        write_data(&mut writer, "distance", edge.distance)?;
+        write_data(&mut writer, "synapse_inhibitory", edge.inhibitory)?;
+        writeln!(writer, "    </edge>")?;
+    }
+    writeln!(writer, "  </graph>")?;
+    writeln!(writer, "</graphml>")?;
+    Ok(())
+}
+
+fn write_data<W, T>(writer: &mut W, key: &str, value: T) -> Result<()>
+where
+    W: Write,
+    T: std::fmt::Display,
+{
+    writeln!(writer, r#"      <data key="{}">{}</data>"#, key, value)?;
+    Ok(())
+}
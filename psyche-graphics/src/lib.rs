@@ -1,4 +1,27 @@
 extern crate obj_exporter;
+extern crate png;
 extern crate psyche_core;
+extern crate serde;
 
+#[cfg(test)]
+mod tests;
+
+pub mod bitmap;
+pub mod color;
+pub mod dot;
+mod font;
+pub mod gexf;
+pub mod gltf;
+pub mod graphml;
+mod network;
 pub mod obj;
+pub mod ply;
+pub mod render;
+pub mod scene;
+pub mod svg;
+
+use psyche_core::error::*;
+
+pub(crate) fn into_string(bytes: Vec<u8>) -> Result<String> {
+    String::from_utf8(bytes).map_err(|e| Error::simple(format!("{}", e)))
+}
@@ -0,0 +1,73 @@
+use psyche_core::brain::Brain;
+use psyche_core::neuron::Position;
+use psyche_core::Scalar;
+use std::collections::HashSet;
+
+pub(crate) struct Node {
+    pub id: String,
+    pub lineage: String,
+    pub position: Position,
+    pub potential: Scalar,
+    pub inhibitory: bool,
+    pub sensor: bool,
+    pub effector: bool,
+}
+
+pub(crate) struct Edge {
+    pub source: String,
+    pub target: String,
+    pub lineage: String,
+    pub receptors: Scalar,
+    pub distance: Scalar,
+    pub inhibitory: bool,
+}
+
+/// Neurons and synapses of brain flattened into plain graph with attributes.
+pub(crate) struct Network {
+    pub id: String,
+    pub nodes: Vec<Node>,
+    pub edges: Vec<Edge>,
+}
+
+impl Network {
+    pub fn new(brain: &Brain) -> Self {
+        let sensors = brain
+            .get_sensors()
+            .into_iter()
+            .filter_map(|id| brain.sensor(id).map(|s| s.target()))
+            .collect::<HashSet<_>>();
+        let effectors = brain
+            .get_effectors()
+            .into_iter()
+            .filter_map(|id| brain.effector(id).map(|e| e.source()))
+            .collect::<HashSet<_>>();
+        Self {
+            id: brain.id().uuid().to_string(),
+            nodes: brain
+                .neurons()
+                .iter()
+                .map(|n| Node {
+                    id: n.id().uuid().to_string(),
+                    lineage: n.lineage().uuid().to_string(),
+                    position: n.position(),
+                    potential: n.potential(),
+                    inhibitory: n.is_inhibitory(),
+                    sensor: sensors.contains(&n.id()),
+                    effector: effectors.contains(&n.id()),
+                })
+                .collect(),
+            edges: brain
+                .synapses()
+                .iter()
+                .map(|s| Edge {
+                    source: s.source().uuid().to_string(),
+                    target: s.target().uuid().to_string(),
+                    lineage: s.lineage().uuid().to_string(),
+                    receptors: s.receptors(),
+                    distance: s.distance(),
+                    inhibitory: s.is_inhibitory(),
+                })
+                .collect(),
+        }
+    }
+}
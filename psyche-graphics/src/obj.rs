@@ -1,35 +1,19 @@
+pub use crate::color::Color;
+use crate::scene::{Config as SceneConfig, Line, Point, Scene};
 use obj_exporter::*;
 use psyche_core::brain::BrainActivityMap;
 use psyche_core::error::*;
-use psyche_core::neuron::Position;
 use psyche_core::Scalar;
 use std::io::Cursor;
-use std::iter::repeat;
 use std::string::FromUtf8Error;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct Config {
-    pub neurons: Option<Color>,
-    pub synapses: Option<Color>,
-    pub impulses: Option<Color>,
-    pub sensors: Option<Color>,
-    pub effectors: Option<Color>,
+    /// Colors of brain elements (points and lines get their own colors).
+    pub scene: SceneConfig,
     pub color_storage: ColorStorage,
 }
 
-impl Default for Config {
-    fn default() -> Self {
-        Self {
-            neurons: Some([255, 0, 255].into()),
-            synapses: Some([0, 0, 255].into()),
-            impulses: Some([192, 192, 255].into()),
-            sensors: Some([255, 255, 0].into()),
-            effectors: Some([128, 0, 0].into()),
-            color_storage: ColorStorage::Nowhere,
-        }
-    }
-}
-
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum ColorStorage {
     Nowhere,
@@ -43,22 +27,6 @@ impl Default for ColorStorage {
     }
 }
 
-/// (R, G, B)
-#[derive(Debug, Copy, Clone)]
-pub struct Color(u8, u8, u8);
-
-impl From<(u8, u8, u8)> for Color {
-    fn from(value: (u8, u8, u8)) -> Self {
-        Self(value.0, value.1, value.2)
-    }
-}
-
-impl From<[u8; 3]> for Color {
-    fn from(value: [u8; 3]) -> Self {
-        Self(value[0], value[1], value[2])
-    }
-}
-
 /// generates OBJ string from activity map.
 /// NOTE: Colors are stored either in vertices normals or texture vertices.
 pub fn generate_string(activity_map: &BrainActivityMap, config: &Config) -> Result<String> {
@@ -71,290 +39,16 @@ pub fn generate_string(activity_map: &BrainActivityMap, config: &Config) -> Resu
 /// generates OBJ bytes from activity map.
 /// NOTE: Colors are stored either in vertices normals or texture vertices.
 pub fn generate(activity_map: &BrainActivityMap, config: &Config) -> Result<Vec<u8>> {
+    let scene = Scene::new(activity_map, &config.scene);
     let mut objects = vec![];
-
-    if let Some(ref neurons) = config.neurons {
-        if !activity_map.neurons.is_empty() {
-            objects.push(Object {
-                name: "neurons".to_owned(),
-                vertices: activity_map
-                    .neurons
-                    .iter()
-                    .map(|p| Vertex {
-                        x: p.x,
-                        y: p.y,
-                        z: p.z,
-                    })
-                    .collect(),
-                tex_vertices: if config.color_storage == ColorStorage::TexVertices {
-                    let Color(r, g, b) = neurons;
-                    repeat(TVertex {
-                        u: Scalar::from(*r) / 255.0,
-                        v: Scalar::from(*g) / 255.0,
-                        w: Scalar::from(*b) / 255.0,
-                    })
-                    .take(activity_map.neurons.len())
-                    .collect()
-                } else {
-                    vec![]
-                },
-                normals: if config.color_storage == ColorStorage::Normals {
-                    let Color(r, g, b) = neurons;
-                    repeat(Vertex {
-                        x: Scalar::from(*r) / 255.0,
-                        y: Scalar::from(*g) / 255.0,
-                        z: Scalar::from(*b) / 255.0,
-                    })
-                    .take(activity_map.neurons.len())
-                    .collect()
-                } else {
-                    vec![]
-                },
-                geometry: vec![Geometry {
-                    material_name: None,
-                    shapes: activity_map
-                        .neurons
-                        .iter()
-                        .enumerate()
-                        .map(|(i, _)| Shape {
-                            primitive: Primitive::Point((i, None, None)),
-                            groups: vec![],
-                            smoothing_groups: vec![],
-                        })
-                        .collect(),
-                }],
-            });
+    for layer in &scene.layers {
+        let points = &scene.points[layer.points.clone()];
+        if !points.is_empty() {
+            objects.push(make_points_object(layer.name, points, config.color_storage));
         }
-    }
-
-    if let Some(ref synapses) = config.synapses {
-        if !activity_map.connections.is_empty() {
-            objects.push(Object {
-                name: "synapses".to_owned(),
-                vertices: activity_map
-                    .connections
-                    .iter()
-                    .flat_map(|(f, t, _)| {
-                        vec![
-                            Vertex {
-                                x: f.x,
-                                y: f.y,
-                                z: f.z,
-                            },
-                            Vertex {
-                                x: t.x,
-                                y: t.y,
-                                z: t.z,
-                            },
-                        ]
-                    })
-                    .collect(),
-                tex_vertices: if config.color_storage == ColorStorage::TexVertices {
-                    let Color(r, g, b) = synapses;
-                    repeat(TVertex {
-                        u: Scalar::from(*r) / 255.0,
-                        v: Scalar::from(*g) / 255.0,
-                        w: Scalar::from(*b) / 255.0,
-                    })
-                    .take(activity_map.connections.len())
-                    .collect()
-                } else {
-                    vec![]
-                },
-                normals: if config.color_storage == ColorStorage::Normals {
-                    let Color(r, g, b) = synapses;
-                    repeat(Vertex {
-                        x: Scalar::from(*r) / 255.0,
-                        y: Scalar::from(*g) / 255.0,
-                        z: Scalar::from(*b) / 255.0,
-                    })
-                    .take(activity_map.connections.len())
-                    .collect()
-                } else {
-                    vec![]
-                },
-                geometry: vec![Geometry {
-                    material_name: None,
-                    shapes: activity_map
-                        .connections
-                        .iter()
-                        .enumerate()
-                        .map(|(i, _)| Shape {
-                            primitive: Primitive::Line(
-                                (i * 2, None, None),
-                                (i * 2 + 1, None, None),
-                            ),
-                            groups: vec![],
-                            smoothing_groups: vec![],
-                        })
-                        .collect(),
-                }],
-            });
-        }
-    }
-
-    if let Some(ref impulses) = config.impulses {
-        if !activity_map.impulses.is_empty() {
-            let positions = activity_map
-                .impulses
-                .iter()
-                .map(|(s, e, f)| lerp(*s, *e, *f))
-                .collect::<Vec<_>>();
-            objects.push(Object {
-                name: "impulses".to_owned(),
-                vertices: positions
-                    .iter()
-                    .map(|p| Vertex {
-                        x: p.x,
-                        y: p.y,
-                        z: p.z,
-                    })
-                    .collect(),
-                tex_vertices: if config.color_storage == ColorStorage::TexVertices {
-                    let Color(r, g, b) = impulses;
-                    repeat(TVertex {
-                        u: Scalar::from(*r) / 255.0,
-                        v: Scalar::from(*g) / 255.0,
-                        w: Scalar::from(*b) / 255.0,
-                    })
-                    .take(positions.len())
-                    .collect()
-                } else {
-                    vec![]
-                },
-                normals: if config.color_storage == ColorStorage::Normals {
-                    let Color(r, g, b) = impulses;
-                    repeat(Vertex {
-                        x: Scalar::from(*r) / 255.0,
-                        y: Scalar::from(*g) / 255.0,
-                        z: Scalar::from(*b) / 255.0,
-                    })
-                    .take(positions.len())
-                    .collect()
-                } else {
-                    vec![]
-                },
-                geometry: vec![Geometry {
-                    material_name: None,
-                    shapes: positions
-                        .iter()
-                        .enumerate()
-                        .map(|(i, _)| Shape {
-                            primitive: Primitive::Point((i, None, None)),
-                            groups: vec![],
-                            smoothing_groups: vec![],
-                        })
-                        .collect(),
-                }],
-            });
-        }
-    }
-
-    if let Some(ref sensors) = config.sensors {
-        if !activity_map.sensors.is_empty() {
-            objects.push(Object {
-                name: "sensors".to_owned(),
-                vertices: activity_map
-                    .sensors
-                    .iter()
-                    .map(|p| Vertex {
-                        x: p.x,
-                        y: p.y,
-                        z: p.z,
-                    })
-                    .collect(),
-                tex_vertices: if config.color_storage == ColorStorage::TexVertices {
-                    let Color(r, g, b) = sensors;
-                    repeat(TVertex {
-                        u: Scalar::from(*r) / 255.0,
-                        v: Scalar::from(*g) / 255.0,
-                        w: Scalar::from(*b) / 255.0,
-                    })
-                    .take(activity_map.sensors.len())
-                    .collect()
-                } else {
-                    vec![]
-                },
-                normals: if config.color_storage == ColorStorage::Normals {
-                    let Color(r, g, b) = sensors;
-                    repeat(Vertex {
-                        x: Scalar::from(*r) / 255.0,
-                        y: Scalar::from(*g) / 255.0,
-                        z: Scalar::from(*b) / 255.0,
-                    })
-                    .take(activity_map.sensors.len())
-                    .collect()
-                } else {
-                    vec![]
-                },
-                geometry: vec![Geometry {
-                    material_name: None,
-                    shapes: activity_map
-                        .sensors
-                        .iter()
-                        .enumerate()
-                        .map(|(i, _)| Shape {
-                            primitive: Primitive::Point((i, None, None)),
-                            groups: vec![],
-                            smoothing_groups: vec![],
-                        })
-                        .collect(),
-                }],
-            });
-        }
-    }
-
-    if let Some(ref effectors) = config.effectors {
-        if !activity_map.effectors.is_empty() {
-            objects.push(Object {
-                name: "effectors".to_owned(),
-                vertices: activity_map
-                    .effectors
-                    .iter()
-                    .map(|p| Vertex {
-                        x: p.x,
-                        y: p.y,
-                        z: p.z,
-                    })
-                    .collect(),
-                tex_vertices: if config.color_storage == ColorStorage::TexVertices {
-                    let Color(r, g, b) = effectors;
-                    repeat(TVertex {
-                        u: Scalar::from(*r) / 255.0,
-                        v: Scalar::from(*g) / 255.0,
-                        w: Scalar::from(*b) / 255.0,
-                    })
-                    .take(activity_map.effectors.len())
-                    .collect()
-                } else {
-                    vec![]
-                },
-                normals: if config.color_storage == ColorStorage::Normals {
-                    let Color(r, g, b) = effectors;
-                    repeat(Vertex {
-                        x: Scalar::from(*r) / 255.0,
-                        y: Scalar::from(*g) / 255.0,
-                        z: Scalar::from(*b) / 255.0,
-                    })
-                    .take(activity_map.effectors.len())
-                    .collect()
-                } else {
-                    vec![]
-                },
-                geometry: vec![Geometry {
-                    material_name: None,
-                    shapes: activity_map
-                        .effectors
-                        .iter()
-                        .enumerate()
-                        .map(|(i, _)| Shape {
-                            primitive: Primitive::Point((i, None, None)),
-                            groups: vec![],
-                            smoothing_groups: vec![],
-                        })
-                        .collect(),
-                }],
-            });
+        let lines = &scene.lines[layer.lines.clone()];
+        if !lines.is_empty() {
+            objects.push(make_lines_object(layer.name, lines, config.color_storage));
         }
     }
 
@@ -367,12 +61,96 @@ pub fn generate(activity_map: &BrainActivityMap, config: &Config) -> Result<Vec<
     Ok(cursor.into_inner())
 }
 
-fn lerp(start: Position, end: Position, factor: Scalar) -> Position {
-    let factor = factor.max(0.0).min(1.0);
-    Position {
-        x: (end.x - start.x) * factor + start.x,
-        y: (end.y - start.y) * factor + start.y,
-        z: (end.z - start.z) * factor + start.z,
+fn make_points_object(name: &str, points: &[Point], color_storage: ColorStorage) -> Object {
+    let colors = points.iter().map(|p| p.color).collect::<Vec<_>>();
+    Object {
+        name: name.to_owned(),
+        vertices: points
+            .iter()
+            .map(|p| Vertex {
+                x: p.position.x,
+                y: p.position.y,
+                z: p.position.z,
+            })
+            .collect(),
+        tex_vertices: make_tex_vertices(&colors, color_storage),
+        normals: make_normals(&colors, color_storage),
+        geometry: vec![Geometry {
+            material_name: None,
+            shapes: (0..points.len())
+                .map(|i| Shape {
+                    primitive: Primitive::Point((i, None, None)),
+                    groups: vec![],
+                    smoothing_groups: vec![],
+                })
+                .collect(),
+        }],
+    }
+}
+
+fn make_lines_object(name: &str, lines: &[Line], color_storage: ColorStorage) -> Object {
+    let colors = lines.iter().map(|l| l.color).collect::<Vec<_>>();
+    Object {
+        name: name.to_owned(),
+        vertices: lines
+            .iter()
+            .flat_map(|l| {
+                vec![
+                    Vertex {
+                        x: l.from.x,
+                        y: l.from.y,
+                        z: l.from.z,
+                    },
+                    Vertex {
+                        x: l.to.x,
+                        y: l.to.y,
+                        z: l.to.z,
+                    },
+                ]
+            })
+            .collect(),
+        tex_vertices: make_tex_vertices(&colors, color_storage),
+        normals: make_normals(&colors, color_storage),
+        geometry: vec![Geometry {
+            material_name: None,
+            shapes: (0..lines.len())
+                .map(|i| Shape {
+                    primitive: Primitive::Line((i * 2, None, None), (i * 2 + 1, None, None)),
+                    groups: vec![],
+                    smoothing_groups: vec![],
+                })
+                .collect(),
+        }],
+    }
+}
+
+fn make_tex_vertices(colors: &[Color], color_storage: ColorStorage) -> Vec<TVertex> {
+    if color_storage == ColorStorage::TexVertices {
+        colors
+            .iter()
+            .map(|Color(r, g, b)| TVertex {
+                u: Scalar::from(*r) / 255.0,
+                v: Scalar::from(*g) / 255.0,
+                w: Scalar::from(*b) / 255.0,
+            })
+            .collect()
+    } else {
+        vec![]
+    }
+}
+
+fn make_normals(colors: &[Color], color_storage: ColorStorage) -> Vec<Vertex> {
+    if color_storage == ColorStorage::Normals {
+        colors
+            .iter()
+            .map(|Color(r, g, b)| Vertex {
+                x: Scalar::from(*r) / 255.0,
+                y: Scalar::from(*g) / 255.0,
+                z: Scalar::from(*b) / 255.0,
+            })
+            .collect()
+    } else {
+        vec![]
     }
 }
 
@@ -0,0 +1,80 @@
+use crate::into_string;
+use crate::scene::{Config, Scene};
+use psyche_core::brain::BrainActivityMap;
+use psyche_core::error::*;
+use std::io::Write;
+
+/// generates ASCII PLY string from activity map.
+/// NOTE: Points (neurons, impulses, sensors, effectors) get `scale` vertex property;
+/// synapses are stored as colored edges between extra vertices.
+pub fn generate_string(activity_map: &BrainActivityMap, config: &Config) -> Result<String> {
+    into_string(generate(activity_map, config)?)
+}
+
+/// generates ASCII PLY bytes from activity map.
+/// NOTE: Points (neurons, impulses, sensors, effectors) get `scale` vertex property;
+/// synapses are stored as colored edges between extra vertices.
+pub fn generate(activity_map: &BrainActivityMap, config: &Config) -> Result<Vec<u8>> {
+    let mut result = vec![];
+    write(activity_map, config, &mut result)?;
+    Ok(result)
+}
+
+/// writes ASCII PLY of activity map.
+pub fn write<W>(activity_map: &BrainActivityMap, config: &Config, mut writer: W) -> Result<()>
+where
+    W: Write,
+{
+    let scene = Scene::new(activity_map, config);
+    writeln!(writer, "ply")?;
+    writeln!(writer, "format ascii 1.0")?;
+    writeln!(writer, "comment Psyche brain activity")?;
+    writeln!(
+        writer,
+        "element vertex {}",
+        scene.points.len() + scene.lines.len() * 2
+    )?;
+    writeln!(writer, "property float x")?;
+    writeln!(writer, "property float y")?;
+    writeln!(writer, "property float z")?;
+    writeln!(writer, "property uchar red")?;
+    writeln!(writer, "property uchar green")?;
+    writeln!(writer, "property uchar blue")?;
+    writeln!(writer, "property float scale")?;
+    writeln!(writer, "element edge {}", scene.lines.len())?;
+    writeln!(writer, "property int vertex1")?;
+    writeln!(writer, "property int vertex2")?;
+    writeln!(writer, "property uchar red")?;
+    writeln!(writer, "property uchar green")?;
+    writeln!(writer, "property uchar blue")?;
+    writeln!(writer, "end_header")?;
+    for point in &scene.points {
+        let p = point.position;
+        let c = point.color;
+        writeln!(
+            writer,
+            "{} {} {} {} {} {} {}",
+            p.x, p.y, p.z, c.0, c.1, c.2, point.size
+        )?;
+    }
+    for line in &scene.lines {
+        let c = line.color;
+        for p in &[line.from, line.to] {
+            writeln!(writer, "{} {} {} {} {} {} 0", p.x, p.y, p.z, c.0, c.1, c.2)?;
+        }
+    }
+    let offset = scene.points.len();
+    for (i, line) in scene.lines.iter().enumerate() {
+        let c = line.color;
+        writeln!(
+            writer,
+            "{} {} {} {} {}",
+            offset + i * 2,
+            offset + i * 2 + 1,
+            c.0,
+            c.1,
+            c.2
+        )?;
+    }
+    Ok(())
+}
@@ -0,0 +1,233 @@
+use crate::color::Color;
+use crate::scene::{Config as SceneConfig, Scene};
+use psyche_core::brain::{BrainActivityMap, BrainActivityStats};
+use psyche_core::neuron::Position;
+use psyche_core::Scalar;
+use std::cmp::Ordering;
+
+/// Projection of brain space onto image plane.
+#[derive(Debug, Clone, Default)]
+pub enum Projection {
+    /// Looking along Z axis.
+    #[default]
+    XY,
+    /// Looking along Y axis.
+    XZ,
+    /// Looking along X axis.
+    YZ,
+    /// Orthographic camera looking from `eye` at `target`.
+    Orthographic {
+        eye: Position,
+        target: Position,
+        up: Position,
+    },
+}
+
+impl Projection {
+    /// Returns (horizontal, vertical, depth) coordinates; bigger depth is further from viewer.
+    pub fn project(&self, position: Position) -> (Scalar, Scalar, Scalar) {
+        match self {
+            Projection::XY => (position.x, position.y, -position.z),
+            Projection::XZ => (position.x, position.z, position.y),
+            Projection::YZ => (position.y, position.z, -position.x),
+            Projection::Orthographic { eye, target, up } => {
+                let forward = normalize(sub(*target, *eye));
+                let right = normalize(cross(forward, *up));
+                let up = cross(right, forward);
+                let relative = sub(position, *eye);
+                (
+                    dot(relative, right),
+                    dot(relative, up),
+                    dot(relative, forward),
+                )
+            }
+        }
+    }
+}
+
+/// Part of image plane shown on image.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct View {
+    /// Center of view in projected coordinates.
+    pub center: (Scalar, Scalar),
+    /// Size of projected space visible along shorter image side.
+    pub size: Scalar,
+}
+
+#[derive(Debug, Clone)]
+pub struct Config {
+    pub width: usize,
+    pub height: usize,
+    pub projection: Projection,
+    /// View that fits activity map is used when not set.
+    /// NOTE: Set it for frame sequences, so view does not change between frames.
+    pub view: Option<View>,
+    pub background: Color,
+    pub text: Color,
+    pub scene: SceneConfig,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            width: 512,
+            height: 512,
+            projection: Default::default(),
+            view: None,
+            background: [16, 16, 24].into(),
+            text: [255, 255, 255].into(),
+            scene: Default::default(),
+        }
+    }
+}
+
+/// Makes view that shows all elements of activity map.
+pub fn fit_view(activity_map: &BrainActivityMap, config: &Config) -> View {
+    let scene = Scene::new(activity_map, &config.scene);
+    let positions = scene
+        .points
+        .iter()
+        .map(|p| p.position)
+        .chain(scene.lines.iter().flat_map(|l| vec![l.from, l.to]))
+        .map(|p| config.projection.project(p))
+        .collect::<Vec<_>>();
+    if positions.is_empty() {
+        return View {
+            center: (0.0, 0.0),
+            size: 1.0,
+        };
+    }
+    let (mut min_x, mut min_y) = (Scalar::INFINITY, Scalar::INFINITY);
+    let (mut max_x, mut max_y) = (Scalar::NEG_INFINITY, Scalar::NEG_INFINITY);
+    for (x, y, _) in positions {
+        min_x = min_x.min(x);
+        min_y = min_y.min(y);
+        max_x = max_x.max(x);
+        max_y = max_y.max(y);
+    }
+    let side = config.width.min(config.height).max(1) as Scalar;
+    let size = ((max_x - min_x) * side / config.width.max(1) as Scalar)
+        .max((max_y - min_y) * side / config.height.max(1) as Scalar)
+        * 1.1;
+    View {
+        center: ((min_x + max_x) * 0.5, (min_y + max_y) * 0.5),
+        size: if size > 0.0 { size } else { 1.0 },
+    }
+}
+
+/// Lines of text describing brain activity stats, ready to put as image overlay.
+pub fn stats_overlay(stats: &BrainActivityStats) -> Vec<String> {
+    vec![
+        format!("neurons: {}", stats.neurons_count),
+        format!("synapses: {}", stats.synapses_count),
+        format!("impulses: {}", stats.impulses_count),
+        format!("neurons potential: {:.3}", stats.neurons_potential.0),
+        format!("impulses potential: {:.3}", stats.impulses_potential.0),
+        format!("mean firing rate: {:.3}", stats.mean_firing_rate),
+        format!("active neurons: {:.3}", stats.active_neurons),
+    ]
+}
+
+pub(crate) struct Circle {
+    pub x: Scalar,
+    pub y: Scalar,
+    pub radius: Scalar,
+    pub color: Color,
+}
+
+pub(crate) struct Segment {
+    pub from: (Scalar, Scalar),
+    pub to: (Scalar, Scalar),
+    pub color: Color,
+}
+
+/// Activity map projected into image space (pixels), points sorted from furthest.
+pub(crate) struct Frame {
+    pub circles: Vec<Circle>,
+    pub segments: Vec<Segment>,
+}
+
+impl Frame {
+    pub fn new(activity_map: &BrainActivityMap, config: &Config) -> Self {
+        let scene = Scene::new(activity_map, &config.scene);
+        let view = config
+            .view
+            .unwrap_or_else(|| fit_view(activity_map, config));
+        let scale = config.width.min(config.height) as Scalar / view.size;
+        let half_width = config.width as Scalar * 0.5;
+        let half_height = config.height as Scalar * 0.5;
+        let to_image = |position| {
+            let (x, y, depth) = config.projection.project(position);
+            (
+                (x - view.center.0) * scale + half_width,
+                half_height - (y - view.center.1) * scale,
+                depth,
+            )
+        };
+        let mut circles = scene
+            .points
+            .iter()
+            .map(|p| {
+                let (x, y, depth) = to_image(p.position);
+                let circle = Circle {
+                    x,
+                    y,
+                    radius: (p.size * scale * 0.5).max(1.0),
+                    color: p.color,
+                };
+                (circle, depth)
+            })
+            .collect::<Vec<_>>();
+        circles.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(Ordering::Equal));
+        let segments = scene
+            .lines
+            .iter()
+            .map(|l| {
+                let (fx, fy, _) = to_image(l.from);
+                let (tx, ty, _) = to_image(l.to);
+                Segment {
+                    from: (fx, fy),
+                    to: (tx, ty),
+                    color: l.color,
+                }
+            })
+            .collect();
+        Self {
+            circles: circles.into_iter().map(|(c, _)| c).collect(),
+            segments,
+        }
+    }
+}
+
+fn sub(a: Position, b: Position) -> Position {
+    Position {
+        x: a.x - b.x,
+        y: a.y - b.y,
+        z: a.z - b.z,
+    }
+}
+
+fn dot(a: Position, b: Position) -> Scalar {
+    a.x * b.x + a.y * b.y + a.z * b.z
+}
+
+fn cross(a: Position, b: Position) -> Position {
+    Position {
+        x: a.y * b.z - a.z * b.y,
+        y: a.z * b.x - a.x * b.z,
+        z: a.x * b.y - a.y * b.x,
+    }
+}
+
+fn normalize(a: Position) -> Position {
+    let magnitude = a.magnitude();
+    if magnitude > 0.0 {
+        Position {
+            x: a.x / magnitude,
+            y: a.y / magnitude,
+            z: a.z / magnitude,
+        }
+    } else {
+        a
+    }
+}
@@ -0,0 +1,220 @@
+use crate::color::{Color, Colormap, Gradient};
+use psyche_core::brain::BrainActivityMap;
+use psyche_core::neuron::Position;
+use psyche_core::Scalar;
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+
+/// Colors and sizes of brain elements shared by all exporters.
+/// NOTE: Activity maps without potentials use start of gradient range.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// Neurons colored by their potential.
+    pub neurons: Option<Gradient>,
+    /// Synapses colored by their receptors.
+    pub synapses: Option<Gradient>,
+    /// Impulses colored by their potential.
+    pub impulses: Option<Gradient>,
+    pub sensors: Option<Color>,
+    pub effectors: Option<Color>,
+    pub inhibitory_neurons: Option<Gradient>,
+    pub inhibitory_synapses: Option<Gradient>,
+    pub inhibitory_impulses: Option<Gradient>,
+    /// Size of neurons and impulses, scaled by factor of their potential within gradient range.
+    /// Sensors and effectors always get the biggest size.
+    pub point_size: Range<Scalar>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            neurons: Some(Gradient::new(
+                Colormap::linear([64, 0, 64].into(), [255, 0, 255].into()),
+                0.0..1.0,
+            )),
+            synapses: Some(Gradient::new(
+                Colormap::linear([0, 0, 64].into(), [0, 0, 255].into()),
+                0.0..2.0,
+            )),
+            impulses: Some(Gradient::new(
+                Colormap::linear([96, 96, 128].into(), [192, 192, 255].into()),
+                0.0..1.0,
+            )),
+            sensors: Some([255, 255, 0].into()),
+            effectors: Some([128, 0, 0].into()),
+            inhibitory_neurons: Some(Gradient::new(
+                Colormap::linear([0, 64, 32].into(), [0, 255, 128].into()),
+                0.0..1.0,
+            )),
+            inhibitory_synapses: Some(Gradient::new(
+                Colormap::linear([0, 32, 0].into(), [0, 128, 0].into()),
+                0.0..2.0,
+            )),
+            inhibitory_impulses: Some(Gradient::new(
+                Colormap::linear([96, 128, 96].into(), [192, 255, 192].into()),
+                0.0..1.0,
+            )),
+            point_size: 0.25..1.0,
+        }
+    }
+}
+
+pub(crate) struct Point {
+    pub position: Position,
+    pub color: Color,
+    pub size: Scalar,
+}
+
+pub(crate) struct Line {
+    pub from: Position,
+    pub to: Position,
+    pub color: Color,
+}
+
+/// Named group of scene points and lines (neurons, synapses, etc.).
+pub(crate) struct Layer {
+    pub name: &'static str,
+    pub points: Range<usize>,
+    pub lines: Range<usize>,
+}
+
+/// Colored and sized primitives of activity map.
+pub(crate) struct Scene {
+    pub points: Vec<Point>,
+    pub lines: Vec<Line>,
+    pub layers: Vec<Layer>,
+}
+
+impl Scene {
+    pub fn new(activity_map: &BrainActivityMap, config: &Config) -> Self {
+        let mut scene = Self {
+            points: vec![],
+            lines: vec![],
+            layers: vec![],
+        };
+        let size = |gradient: &Gradient, value| {
+            let factor = gradient.factor(value);
+            (config.point_size.end - config.point_size.start) * factor + config.point_size.start
+        };
+
+        for (name, positions, potentials, gradient) in &[
+            (
+                "neurons",
+                &activity_map.neurons,
+                &activity_map.neurons_potential,
+                &config.neurons,
+            ),
+            (
+                "inhibitory_neurons",
+                &activity_map.inhibitory_neurons,
+                &activity_map.inhibitory_neurons_potential,
+                &config.inhibitory_neurons,
+            ),
+        ] {
+            if let Some(gradient) = gradient {
+                scene.push_layer(name, |points, _| {
+                    for (i, position) in positions.iter().enumerate() {
+                        let potential = potentials.get(i).cloned().unwrap_or(gradient.range.start);
+                        points.push(Point {
+                            position: *position,
+                            color: gradient.color(potential),
+                            size: size(gradient, potential),
+                        });
+                    }
+                });
+            }
+        }
+
+        for (name, connections, gradient) in &[
+            ("synapses", &activity_map.connections, &config.synapses),
+            (
+                "inhibitory_synapses",
+                &activity_map.inhibitory_connections,
+                &config.inhibitory_synapses,
+            ),
+        ] {
+            if let Some(gradient) = gradient {
+                scene.push_layer(name, |_, lines| {
+                    for (from, to, receptors) in connections.iter() {
+                        lines.push(Line {
+                            from: *from,
+                            to: *to,
+                            color: gradient.color(*receptors),
+                        });
+                    }
+                });
+            }
+        }
+
+        for (name, impulses, potentials, gradient) in &[
+            (
+                "impulses",
+                &activity_map.impulses,
+                &activity_map.impulses_potential,
+                &config.impulses,
+            ),
+            (
+                "inhibitory_impulses",
+                &activity_map.inhibitory_impulses,
+                &activity_map.inhibitory_impulses_potential,
+                &config.inhibitory_impulses,
+            ),
+        ] {
+            if let Some(gradient) = gradient {
+                scene.push_layer(name, |points, _| {
+                    for (i, (from, to, factor)) in impulses.iter().enumerate() {
+                        let potential = potentials.get(i).cloned().unwrap_or(gradient.range.start);
+                        points.push(Point {
+                            position: lerp(*from, *to, *factor),
+                            color: gradient.color(potential),
+                            size: size(gradient, potential),
+                        });
+                    }
+                });
+            }
+        }
+
+        for (name, positions, color) in &[
+            ("sensors", &activity_map.sensors, config.sensors),
+            ("effectors", &activity_map.effectors, config.effectors),
+        ] {
+            if let Some(color) = color {
+                scene.push_layer(name, |points, _| {
+                    for position in positions.iter() {
+                        points.push(Point {
+                            position: *position,
+                            color: *color,
+                            size: config.point_size.end,
+                        });
+                    }
+                });
+            }
+        }
+
+        scene
+    }
+
+    fn push_layer<F>(&mut self, name: &'static str, mut f: F)
+    where
+        F: FnMut(&mut Vec<Point>, &mut Vec<Line>),
+    {
+        let points = self.points.len();
+        let lines = self.lines.len();
+        f(&mut self.points, &mut self.lines);
+        self.layers.push(Layer {
+            name,
+            points: points..self.points.len(),
+            lines: lines..self.lines.len(),
+        });
+    }
+}
+
+pub(crate) fn lerp(start: Position, end: Position, factor: Scalar) -> Position {
+    let factor = factor.clamp(0.0, 1.0);
+    Position {
+        x: (end.x - start.x) * factor + start.x,
+        y: (end.y - start.y) * factor + start.y,
+        z: (end.z - start.z) * factor + start.z,
+    }
+}
@@ -0,0 +1,93 @@
+use crate::color::Color;
+use crate::into_string;
+use crate::render::{Config, Frame};
+use psyche_core::brain::BrainActivityMap;
+use psyche_core::error::*;
+use std::io::Write;
+
+/// generates SVG image string of activity map projected onto image plane.
+/// NOTE: Each of `overlay` lines is rendered as text in top left corner.
+pub fn generate_string(
+    activity_map: &BrainActivityMap,
+    config: &Config,
+    overlay: &[String],
+) -> Result<String> {
+    into_string(generate(activity_map, config, overlay)?)
+}
+
+/// generates SVG image bytes of activity map projected onto image plane.
+/// NOTE: Each of `overlay` lines is rendered as text in top left corner.
+pub fn generate(
+    activity_map: &BrainActivityMap,
+    config: &Config,
+    overlay: &[String],
+) -> Result<Vec<u8>> {
+    let mut result = vec![];
+    write(activity_map, config, overlay, &mut result)?;
+    Ok(result)
+}
+
+/// writes SVG image of activity map projected onto image plane.
+pub fn write<W>(
+    activity_map: &BrainActivityMap,
+    config: &Config,
+    overlay: &[String],
+    mut writer: W,
+) -> Result<()>
+where
+    W: Write,
+{
+    let frame = Frame::new(activity_map, config);
+    writeln!(
+        writer,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{0}" height="{1}" viewBox="0 0 {0} {1}">"#,
+        config.width, config.height
+    )?;
+    writeln!(
+        writer,
+        r#"  <rect width="100%" height="100%" fill="{}"/>"#,
+        hex(config.background)
+    )?;
+    for segment in &frame.segments {
+        writeln!(
+            writer,
+            r#"  <line x1="{:.2}" y1="{:.2}" x2="{:.2}" y2="{:.2}" stroke="{}"/>"#,
+            segment.from.0,
+            segment.from.1,
+            segment.to.0,
+            segment.to.1,
+            hex(segment.color)
+        )?;
+    }
+    for circle in &frame.circles {
+        writeln!(
+            writer,
+            r#"  <circle cx="{:.2}" cy="{:.2}" r="{:.2}" fill="{}"/>"#,
+            circle.x,
+            circle.y,
+            circle.radius,
+            hex(circle.color)
+        )?;
+    }
+    for (i, line) in overlay.iter().enumerate() {
+        writeln!(
+            writer,
+            r#"  <text x="8" y="{}" font-family="monospace" font-size="12" fill="{}">{}</text>"#,
+            20 + i * 14,
+            hex(config.text),
+            escape(line)
+        )?;
+    }
+    writeln!(writer, "</svg>")?;
+    Ok(())
+}
+
+fn hex(color: Color) -> String {
+    format!("#{:02x}{:02x}{:02x}", color.0, color.1, color.2)
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
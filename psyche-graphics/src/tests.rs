@@ -0,0 +1,229 @@
+#![cfg(test)]
+use crate::color::{Color, Colormap, Gradient};
+use crate::render::{fit_view, stats_overlay, Projection};
+use crate::scene::Config;
+use crate::*;
+use psyche_core::brain::*;
+use psyche_core::neuron::*;
+
+fn make_brain() -> Brain {
+    let mut brain = Brain::new();
+    let n1 = brain.create_neuron(Position {
+        x: 0.0,
+        y: 0.0,
+        z: 0.0,
+    });
+    let n2 = brain.create_neuron(Position {
+        x: 1.0,
+        y: 0.0,
+        z: 0.0,
+    });
+    let n3 = brain.create_neuron(Position {
+        x: 2.0,
+        y: 1.0,
+        z: 0.0,
+    });
+    brain.create_sensor(n1).unwrap();
+    brain.bind_neurons(n1, n2).unwrap();
+    brain.bind_neurons(n2, n3).unwrap();
+    brain.create_effector(n3).unwrap();
+    brain
+}
+
+#[test]
+fn test_graph_exporters() {
+    let brain = make_brain();
+    let n1 = brain.neurons()[0].id().uuid().to_string();
+    let n2 = brain.neurons()[1].id().uuid().to_string();
+
+    let graphml = graphml::generate_string(&brain).unwrap();
+    assert_eq!(graphml.matches("<node ").count(), 3);
+    assert_eq!(graphml.matches("<edge ").count(), 2);
+    assert!(graphml.contains(&format!(r#"<edge source="{}" target="{}">"#, n1, n2)));
+    assert_eq!(
+        graphml.matches(r#"<data key="sensor">true</data>"#).count(),
+        1
+    );
+    assert!(graphml.contains(&format!(
+        r#"<data key="lineage">{}</data>"#,
+        brain.neurons()[0].lineage().uuid()
+    )));
+
+    let dot = dot::generate_string(&brain).unwrap();
+    assert!(dot.starts_with("digraph "));
+    assert_eq!(dot.matches(" -> ").count(), 2);
+    assert!(dot.contains(&format!(r#""{}" -> "{}""#, n1, n2)));
+    assert_eq!(dot.matches(r#"effector="true""#).count(), 1);
+
+    let gexf = gexf::generate_string(&brain).unwrap();
+    assert_eq!(gexf.matches("<node ").count(), 3);
+    assert_eq!(gexf.matches("<edge ").count(), 2);
+    assert_eq!(gexf.matches("<viz:position ").count(), 3);
+}
+
+#[test]
+fn test_activity_exporters() {
+    let mut brain = make_brain();
+    let config = Config::default();
+    let sensor = brain.get_sensors()[0];
+    brain.sensor_trigger_impulse(sensor, 1.0).unwrap();
+    brain.process(0.1).unwrap();
+    let map = brain.build_activity_map(activity::ALL);
+
+    let ply = ply::generate_string(&map, &config).unwrap();
+    let points = map.neurons.len() + map.impulses.len() + map.sensors.len() + map.effectors.len();
+    assert!(ply.contains(&format!(
+        "element vertex {}\n",
+        points + map.connections.len() * 2
+    )));
+    assert!(ply.contains(&format!("element edge {}\n", map.connections.len())));
+    assert_eq!(ply.lines().count(), 18 + points + map.connections.len() * 3);
+
+    let mut animation = gltf::Animation::new(config);
+    for _ in 0..3 {
+        brain.process(0.1).unwrap();
+        animation.push(brain.time(), &brain.build_activity_map(activity::ALL));
+    }
+    let glb = animation.generate().unwrap();
+    assert_eq!(&glb[0..4], b"glTF");
+    assert_eq!(read_u32(&glb, 8), glb.len());
+    let json_length = read_u32(&glb, 12);
+    let json: serde_json::Value = serde_json::from_slice(&glb[20..(20 + json_length)]).unwrap();
+    assert_eq!(json["nodes"].as_array().unwrap().len(), 3);
+    assert_eq!(
+        json["animations"][0]["channels"].as_array().unwrap().len(),
+        3
+    );
+    let buffer_length = read_u32(&glb, 20 + json_length);
+    assert_eq!(
+        json["buffers"][0]["byteLength"].as_u64().unwrap() as usize,
+        buffer_length
+    );
+
+    animation.push(0.0, &map);
+    assert!(animation.generate().is_err());
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> usize {
+    let mut value = [0; 4];
+    value.copy_from_slice(&bytes[offset..(offset + 4)]);
+    u32::from_le_bytes(value) as usize
+}
+
+#[test]
+fn test_renderers() {
+    let brain = make_brain();
+    let map = brain.build_activity_map(activity::ALL);
+    let mut config = render::Config {
+        width: 64,
+        height: 32,
+        scene: Config {
+            point_size: 0.1..0.1,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let view = fit_view(&map, &config);
+    assert!((view.center.0 - 1.0).abs() < 1.0e-6);
+    assert!((view.center.1 - 0.5).abs() < 1.0e-6);
+    assert!((view.size - 1.1).abs() < 1.0e-6);
+    let overlay = stats_overlay(&brain.build_activity_stats());
+
+    let svg = svg::generate_string(&map, &config, &overlay).unwrap();
+    assert!(svg.starts_with("<svg "));
+    assert_eq!(
+        svg.matches("<circle ").count(),
+        map.neurons.len() + map.sensors.len() + map.effectors.len()
+    );
+    assert_eq!(svg.matches("<line ").count(), map.connections.len());
+    assert_eq!(svg.matches("<text ").count(), overlay.len());
+
+    let bitmap = bitmap::render(&map, &config, &[]);
+    assert_eq!(bitmap.pixels().len(), 64 * 32 * 3);
+    assert_eq!(bitmap.pixel(0, 31), Some(config.background));
+    assert_ne!(bitmap.pixel(32, 30), Some(config.background));
+    let png = bitmap.to_png().unwrap();
+    assert_eq!(&png[1..4], b"PNG");
+
+    let mut bitmap = bitmap::Bitmap::new(16, 16, Color(0, 0, 0));
+    bitmap.draw_text(0, 0, "1", 1, Color(255, 255, 255));
+    assert_eq!(bitmap.pixel(1, 0), Some(Color(255, 255, 255)));
+    assert_eq!(bitmap.pixel(0, 0), Some(Color(0, 0, 0)));
+    bitmap.draw_line((-100.0, 8.0), (100.0, 8.0), Color(255, 0, 0));
+    assert_eq!(bitmap.pixel(15, 8), Some(Color(255, 0, 0)));
+
+    config.projection = Projection::Orthographic {
+        eye: Position {
+            x: 0.0,
+            y: 0.0,
+            z: 10.0,
+        },
+        target: Position {
+            x: 0.0,
+            y: 0.0,
+            z: 0.0,
+        },
+        up: Position {
+            x: 0.0,
+            y: 1.0,
+            z: 0.0,
+        },
+    };
+    let (x, y, depth) = config.projection.project(Position {
+        x: 2.0,
+        y: 1.0,
+        z: 0.0,
+    });
+    assert!((x - 2.0).abs() < 1.0e-6);
+    assert!((y - 1.0).abs() < 1.0e-6);
+    assert!((depth - 10.0).abs() < 1.0e-6);
+}
+
+#[test]
+fn test_colormaps() {
+    assert_eq!(Colormap::Viridis.sample(0.0), Color(68, 1, 84));
+    assert_eq!(Colormap::Viridis.sample(1.0), Color(253, 231, 37));
+    assert_eq!(Colormap::Heat.sample(0.5), Color(255, 128, 0));
+    assert_eq!(Colormap::Solid(Color(1, 2, 3)).sample(0.7), Color(1, 2, 3));
+    let stops = Colormap::Stops(vec![
+        (0.0, Color(0, 0, 0)),
+        (0.5, Color(100, 0, 0)),
+        (1.0, Color(100, 200, 0)),
+    ]);
+    assert_eq!(stops.sample(-1.0), Color(0, 0, 0));
+    assert_eq!(stops.sample(0.25), Color(50, 0, 0));
+    assert_eq!(stops.sample(0.75), Color(100, 100, 0));
+    assert_eq!(stops.sample(2.0), Color(100, 200, 0));
+
+    let gradient = Gradient::new(Colormap::linear(Color(0, 0, 0), Color(200, 0, 0)), 1.0..3.0);
+    assert_eq!(gradient.color(2.0), Color(100, 0, 0));
+    let config = Config {
+        synapses: Some(gradient),
+        neurons: Some(Gradient::new(Colormap::Viridis, 0.0..1.0)),
+        ..Default::default()
+    };
+    let json = serde_json::to_string(&config).unwrap();
+    let loaded: Config = serde_json::from_str(&json).unwrap();
+    assert_eq!(loaded, config);
+    let loaded: Config =
+        serde_json::from_str(r#"{"neurons":{"colormap":"Heat","range":{"start":0,"end":2}}}"#)
+            .unwrap();
+    assert_eq!(loaded.neurons.unwrap().colormap, Colormap::Heat);
+    assert_eq!(loaded.synapses, Config::default().synapses);
+
+    let map = BrainActivityMap {
+        neurons: vec![Default::default(), Default::default()],
+        neurons_potential: vec![1.0, 0.0],
+        ..Default::default()
+    };
+    let config = obj::Config {
+        scene: Config {
+            neurons: Some(Gradient::new(Colormap::Heat, 0.0..1.0)),
+            ..Default::default()
+        },
+        color_storage: obj::ColorStorage::Normals,
+    };
+    let obj = obj::generate_string(&map, &config).unwrap();
+    assert!(obj.contains("vn 1.000000 1.000000 1.000000"));
+    assert!(obj.contains("vn 0.000000 0.000000 0.000000"));
+}
@@ -3,6 +3,8 @@ use psyche_core::brain::{Brain, BrainActivityMap};
 use psyche_core::brain_builder::BrainBuilder;
 use psyche_core::config::Config;
 use psyche_core::offspring_builder::OffspringBuilder;
+use psyche_core::recorder::Recorder;
+use psyche_evolution::population::Population;
 
 #[inline]
 pub fn brain_to_bytes(brain: &Brain) -> BinResult<Vec<u8>> {
@@ -53,3 +55,23 @@ pub fn offspring_builder_to_bytes(offspring_builder: &OffspringBuilder) -> BinRe
 pub fn offspring_builder_from_bytes(bytes: &[u8]) -> BinResult<OffspringBuilder> {
     bincode::deserialize(bytes)
 }
+
+#[inline]
+pub fn recorder_to_bytes(recorder: &Recorder) -> BinResult<Vec<u8>> {
+    bincode::serialize(recorder)
+}
+
+#[inline]
+pub fn recorder_from_bytes(bytes: &[u8]) -> BinResult<Recorder> {
+    bincode::deserialize(bytes)
+}
+
+#[inline]
+pub fn population_to_bytes(population: &Population) -> BinResult<Vec<u8>> {
+    bincode::serialize(population)
+}
+
+#[inline]
+pub fn population_from_bytes(bytes: &[u8]) -> BinResult<Population> {
+    bincode::deserialize(bytes)
+}
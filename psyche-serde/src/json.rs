@@ -2,6 +2,7 @@ use psyche_core::brain::{Brain, BrainActivityMap};
 use psyche_core::brain_builder::BrainBuilder;
 use psyche_core::config::Config;
 use psyche_core::offspring_builder::OffspringBuilder;
+use psyche_evolution::population::Population;
 use serde_json::Result as JsonResult;
 
 #[inline]
@@ -76,3 +77,17 @@ pub fn offspring_builder_to_json(
 pub fn offspring_builder_from_json(json: &str) -> JsonResult<OffspringBuilder> {
     serde_json::from_str(json)
 }
+
+#[inline]
+pub fn population_to_json(population: &Population, pretty: bool) -> JsonResult<String> {
+    if pretty {
+        serde_json::to_string_pretty(population)
+    } else {
+        serde_json::to_string(population)
+    }
+}
+
+#[inline]
+pub fn population_from_json(json: &str) -> JsonResult<Population> {
+    serde_json::from_str(json)
+}
@@ -1,5 +1,6 @@
 extern crate bincode;
 extern crate psyche_core;
+extern crate psyche_evolution;
 extern crate serde_json;
 extern crate serde_yaml;
 
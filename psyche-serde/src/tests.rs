@@ -4,8 +4,16 @@ use crate::json::*;
 use crate::yaml::*;
 use psyche_core::brain::activity;
 use psyche_core::brain::*;
+use psyche_core::brain_builder::*;
 use psyche_core::config::*;
+use psyche_core::error::*;
+use psyche_core::label::*;
 use psyche_core::neuron::*;
+use psyche_core::offspring_builder::*;
+use psyche_core::recorder::*;
+use psyche_core::rng::*;
+use psyche_core::Scalar;
+use psyche_evolution::population::*;
 
 #[test]
 fn test_brain() {
@@ -25,10 +33,16 @@ fn test_brain() {
         y: 0.0,
         z: 0.0,
     });
-    brain.create_sensor(n1).unwrap();
+    let s1 = brain.create_sensor(n1).unwrap();
     brain.bind_neurons(n1, n2).unwrap();
     brain.bind_neurons(n2, n3).unwrap();
-    brain.create_effector(n3).unwrap();
+    let e1 = brain.create_effector(n3).unwrap();
+    brain
+        .set_sensor_label(s1, Label::new().name("eye").tag("vision"))
+        .unwrap();
+    brain
+        .set_effector_label(e1, Label::new().name("leg").meta("side", "left"))
+        .unwrap();
 
     let json = brain_to_json(&brain, true).unwrap();
     let brain_json = brain_from_json(&json).unwrap();
@@ -43,6 +57,185 @@ fn test_brain() {
     assert_eq!(brain, brain_yaml);
 }
 
+#[test]
+fn test_brain_validate() {
+    let mut brain = Brain::new();
+    let n1 = brain.create_neuron(Position {
+        x: 0.0,
+        y: 0.0,
+        z: 0.0,
+    });
+    let n2 = brain.create_neuron(Position {
+        x: 1.0,
+        y: 0.0,
+        z: 0.0,
+    });
+    brain.create_sensor(n1).unwrap();
+    brain.bind_neurons(n1, n2).unwrap();
+    brain.create_effector(n2).unwrap();
+    assert!(brain.validate().is_ok());
+
+    let json = serde_json::to_value(&brain).unwrap();
+    let corrupt = |f: &dyn Fn(&mut serde_json::Value)| {
+        let mut json = json.clone();
+        f(&mut json);
+        brain_from_json(&json.to_string()).unwrap().validate()
+    };
+    assert!(corrupt(&|_| {}).is_ok());
+    assert!(corrupt(&|json| {
+        json["neurons"].as_array_mut().unwrap().remove(1);
+    })
+    .is_err());
+    assert!(corrupt(&|json| {
+        let synapse = json["synapses"][0].clone();
+        json["synapses"].as_array_mut().unwrap().push(synapse);
+    })
+    .is_err());
+    assert!(corrupt(&|json| {
+        json["synapses"][0]["target"] = json["synapses"][0]["source"].clone();
+    })
+    .is_err());
+    assert!(corrupt(&|json| {
+        json["neurons"][0]["owner_id"] = json["neurons"][0]["id"].clone();
+    })
+    .is_err());
+    match corrupt(&|json| {
+        let sensor = json["sensors"][0].clone();
+        json["sensors"].as_array_mut().unwrap().push(sensor);
+    }) {
+        Err(Error::DuplicatedSensor(_)) => {}
+        result => panic!("{:?}", result),
+    }
+    match corrupt(&|json| {
+        let effector = json["effectors"][0].clone();
+        json["effectors"].as_array_mut().unwrap().push(effector);
+    }) {
+        Err(Error::DuplicatedEffector(_)) => {}
+        result => panic!("{:?}", result),
+    }
+    match corrupt(&|json| {
+        let mut sensor = json["sensors"][0].clone();
+        sensor["id"] = json["id"].clone();
+        json["sensors"].as_array_mut().unwrap().push(sensor);
+    }) {
+        Err(Error::NeuronHasMultipleSensors(_)) => {}
+        result => panic!("{:?}", result),
+    }
+    match corrupt(&|json| {
+        let mut effector = json["effectors"][0].clone();
+        effector["id"] = json["id"].clone();
+        json["effectors"].as_array_mut().unwrap().push(effector);
+    }) {
+        Err(Error::NeuronHasMultipleEffectors(_)) => {}
+        result => panic!("{:?}", result),
+    }
+    match corrupt(&|json| {
+        json["effectors"][0]["source"] = json["sensors"][0]["target"].clone();
+    }) {
+        Err(Error::NeuronIsBothSensorAndEffector(_)) => {}
+        result => panic!("{:?}", result),
+    }
+
+    // JSON can not store non-finite numbers, so these are corrupted through YAML.
+    let yaml = serde_yaml::to_value(&brain).unwrap();
+    let corrupt = |f: &dyn Fn(&mut serde_yaml::Value)| {
+        let mut yaml = yaml.clone();
+        f(&mut yaml);
+        brain_from_yaml(&serde_yaml::to_string(&yaml).unwrap())
+            .unwrap()
+            .validate()
+    };
+    assert!(corrupt(&|_| {}).is_ok());
+    match corrupt(&|yaml| {
+        yaml["sensors"][0]["value"] = Scalar::NAN.into();
+    }) {
+        Err(Error::SensorHasInvalidState(_)) => {}
+        result => panic!("{:?}", result),
+    }
+    match corrupt(&|yaml| {
+        yaml["sensors"][0]["phase"] = Scalar::INFINITY.into();
+    }) {
+        Err(Error::SensorHasInvalidState(_)) => {}
+        result => panic!("{:?}", result),
+    }
+    match corrupt(&|yaml| {
+        yaml["neurons"][0]["recovery"] = Scalar::NAN.into();
+    }) {
+        Err(Error::NeuronHasInvalidState(_)) => {}
+        result => panic!("{:?}", result),
+    }
+    match corrupt(&|yaml| {
+        yaml["neurons"][1]["refractory"] = Scalar::NEG_INFINITY.into();
+    }) {
+        Err(Error::NeuronHasInvalidState(_)) => {}
+        result => panic!("{:?}", result),
+    }
+}
+
+#[test]
+fn test_brain_lineage_snapshot() {
+    let brain = BrainBuilder::new()
+        .neurons(20)
+        .connections(40)
+        .sensors(2)
+        .effectors(2)
+        .seed(42)
+        .build();
+    let mut json = serde_json::to_value(&brain).unwrap();
+    for neuron in json["neurons"].as_array_mut().unwrap() {
+        neuron.as_object_mut().unwrap().remove("lineage");
+    }
+    for synapse in json["synapses"].as_array_mut().unwrap() {
+        synapse.as_object_mut().unwrap().remove("lineage");
+    }
+    let json = json.to_string();
+
+    // snapshots without lineage restore the same lineages every time they are loaded.
+    let first = brain_from_json(&json).unwrap();
+    let second = brain_from_json(&json).unwrap();
+    assert_eq!(first, second);
+    assert_eq!(first, brain);
+}
+
+#[test]
+fn test_brain_snapshot_continuation() {
+    let mut brain = Brain::with_seed(42);
+    brain.set_config(Config {
+        synapse_reconnection_range: Some(0.5),
+        synapse_new_connection_receptors: Some(1.0),
+        ..Default::default()
+    });
+    let neurons = (0..10)
+        .map(|i| {
+            brain.create_neuron(Position {
+                x: i as f64,
+                y: 0.0,
+                z: 0.0,
+            })
+        })
+        .collect::<Vec<_>>();
+    for pair in neurons.windows(2) {
+        brain.bind_neurons(pair[0], pair[1]).unwrap();
+    }
+    brain.ignite_random_synapses(20, 1.0..5.0);
+    for _ in 0..10 {
+        brain.process(0.1).unwrap();
+    }
+
+    let mut brain_json = brain_from_json(&brain_to_json(&brain, false).unwrap()).unwrap();
+    let mut brain_bytes = brain_from_bytes(&brain_to_bytes(&brain).unwrap()).unwrap();
+    let mut brain_yaml = brain_from_yaml(&brain_to_yaml(&brain).unwrap()).unwrap();
+    for _ in 0..40 {
+        brain.process(0.1).unwrap();
+        brain_json.process(0.1).unwrap();
+        brain_bytes.process(0.1).unwrap();
+        brain_yaml.process(0.1).unwrap();
+    }
+    assert_eq!(brain, brain_json);
+    assert_eq!(brain, brain_bytes);
+    assert_eq!(brain, brain_yaml);
+}
+
 #[test]
 fn test_brain_activity_map() {
     let mut brain = Brain::new();
@@ -98,3 +291,82 @@ fn test_config() {
     let config_yaml = config_from_yaml(&yaml).unwrap();
     assert_eq!(config, config_yaml);
 }
+
+#[test]
+fn test_recorder() {
+    let mut brain = Brain::new();
+    let n1 = brain.create_neuron(Position {
+        x: 0.0,
+        y: 0.0,
+        z: 0.0,
+    });
+    let n2 = brain.create_neuron(Position {
+        x: 1.0,
+        y: 0.0,
+        z: 0.0,
+    });
+    brain.bind_neurons(n1, n2).unwrap();
+    let mut recorder = Recorder::new().neurons(vec![n1, n2]).stats(true);
+    for _ in 0..5 {
+        brain.process(0.1).unwrap();
+        recorder.record(&brain);
+    }
+
+    let bytes = recorder_to_bytes(&recorder).unwrap();
+    let recorder_bytes = recorder_from_bytes(&bytes).unwrap();
+    assert_eq!(recorder, recorder_bytes);
+}
+
+#[test]
+fn test_population() {
+    let brain_builder = BrainBuilder::new().neurons(5).connections(10);
+    let mut population =
+        Population::from_builder_with_rng(&brain_builder, 4, &mut BrainRng::from_u64(42));
+    let fitness = |brain: &Brain| brain.synapses().len() as Scalar;
+    population.step(fitness).unwrap();
+
+    let json = population_to_json(&population, true).unwrap();
+    let bytes = population_to_bytes(&population).unwrap();
+    let yaml = population_to_yaml(&population).unwrap();
+    let mut population_json = population_from_json(&json).unwrap();
+    let mut population_bytes = population_from_bytes(&bytes).unwrap();
+    let mut population_yaml = population_from_yaml(&yaml).unwrap();
+
+    // checkpoint continues evolution with the exact same random sequence.
+    let stats = population.step(fitness).unwrap();
+    for checkpoint in &mut [
+        &mut population_json,
+        &mut population_bytes,
+        &mut population_yaml,
+    ] {
+        assert_eq!(checkpoint.history().len(), 1);
+        assert_eq!(checkpoint.step(fitness).unwrap(), stats);
+        assert_eq!(checkpoint.members(), population.members());
+    }
+}
+
+#[test]
+fn test_offspring_builder() {
+    let offspring_builder = OffspringBuilder::new().config_mutation(ConfigMutation {
+        propagation_speed: Some(ScalarMutation::new(0.5, 0.1, 0.5..2.0)),
+        synapse_reconnection_range: Some(OptionMutation::new(
+            0.1,
+            ScalarMutation::new(0.5, 1.0, 1.0..5.0),
+        )),
+        ..Default::default()
+    });
+    let json = offspring_builder_to_json(&offspring_builder, false).unwrap();
+    let yaml = offspring_builder_to_yaml(&offspring_builder).unwrap();
+    let bytes = offspring_builder_to_bytes(&offspring_builder).unwrap();
+    for offspring_builder in &[
+        offspring_builder_from_json(&json).unwrap(),
+        offspring_builder_from_yaml(&yaml).unwrap(),
+        offspring_builder_from_bytes(&bytes).unwrap(),
+    ] {
+        assert_eq!(
+            offspring_builder_to_json(offspring_builder, false).unwrap(),
+            json
+        );
+    }
+    assert!(json.contains(r#""propagation_speed":{"rate":0.5"#));
+}
@@ -2,6 +2,7 @@ use psyche_core::brain::{Brain, BrainActivityMap};
 use psyche_core::brain_builder::BrainBuilder;
 use psyche_core::config::Config;
 use psyche_core::offspring_builder::OffspringBuilder;
+use psyche_evolution::population::Population;
 use serde_yaml::Result as YamlResult;
 
 #[inline]
@@ -53,3 +54,13 @@ pub fn offspring_builder_to_yaml(offspring_builder: &OffspringBuilder) -> YamlRe
 pub fn offspring_builder_from_yaml(yaml: &str) -> YamlResult<OffspringBuilder> {
     serde_yaml::from_str(yaml)
 }
+
+#[inline]
+pub fn population_to_yaml(population: &Population) -> YamlResult<String> {
+    serde_yaml::to_string(population)
+}
+
+#[inline]
+pub fn population_from_yaml(yaml: &str) -> YamlResult<Population> {
+    serde_yaml::from_str(yaml)
+}
@@ -9,16 +9,26 @@ mod timeline;
 
 use clap::{App, Arg, ArgMatches, SubCommand};
 use core::str::from_utf8;
-use psyche::core::brain::{activity, Brain, BrainActivityStats};
+use psyche::core::brain::{activity, Brain, BrainActivityMap, BrainActivityStats};
 use psyche::core::brain_builder::BrainBuilder;
 use psyche::core::config::Config;
 use psyche::core::error::*;
+use psyche::core::recorder::Recorder;
+use psyche::core::rng::BrainRng;
 use psyche::core::Scalar;
-use psyche::graphics::obj::generate;
+use psyche::graphics::bitmap;
+use psyche::graphics::gltf::Animation;
+use psyche::graphics::obj::{generate, Config as ObjConfig};
+use psyche::graphics::ply;
+use psyche::graphics::render::{fit_view, stats_overlay, Config as RenderConfig, Projection};
+use psyche::graphics::scene::Config as SceneConfig;
+use psyche::graphics::svg;
+use psyche::serde::bytes::{brain_from_bytes, recorder_to_bytes};
 use psyche::serde::json::{brain_builder_from_json, brain_builder_to_json, brain_from_json};
 use psyche::serde::yaml::{brain_builder_from_yaml, brain_builder_to_yaml, brain_from_yaml};
-use rand::{thread_rng, Rng};
-use std::fs::{read, write};
+use rand::Rng;
+use std::fmt::Display;
+use std::fs::{read, write, File};
 use std::path::Path;
 use std::time::Instant;
 use timeline::{ActionType, Timeline};
@@ -82,6 +92,14 @@ fn main() -> Result<()> {
                 .takes_value(true)
                 .default_value("activity"),
         )
+        .arg(
+            Arg::with_name("seed")
+                .long("seed")
+                .value_name("INTEGER")
+                .help("Random number generator seed (makes simulation reproducible)")
+                .takes_value(true)
+                .required(false),
+        )
         .arg(
             Arg::with_name("ignore-neurons")
                 .long("ignore-neurons")
@@ -113,6 +131,53 @@ fn main() -> Result<()> {
                 .long("dry")
                 .help("Dry mode (without rendering to files)"),
         )
+        .arg(
+            Arg::with_name("ply")
+                .long("ply")
+                .help("Render frames also as PLY files (colored by potential and receptors)"),
+        )
+        .arg(
+            Arg::with_name("gltf")
+                .long("gltf")
+                .help("Render whole simulation as single animated glTF binary file"),
+        )
+        .arg(
+            Arg::with_name("colors")
+                .short("c")
+                .long("colors")
+                .value_name("FILE")
+                .help("Colors config file path (colormaps of rendered brain elements)")
+                .takes_value(true)
+                .required(false),
+        )
+        .arg(
+            Arg::with_name("svg")
+                .long("svg")
+                .help("Render frames also as SVG images"),
+        )
+        .arg(
+            Arg::with_name("png")
+                .long("png")
+                .help("Render frames also as PNG images"),
+        )
+        .arg(
+            Arg::with_name("projection")
+                .long("projection")
+                .value_name("PLANE")
+                .help("Plane that SVG and PNG images are projected onto (xy, xz, yz)")
+                .takes_value(true)
+                .default_value("xy"),
+        )
+        .arg(
+            Arg::with_name("overlay")
+                .long("overlay")
+                .help("Put brain stats text on SVG and PNG images"),
+        )
+        .arg(
+            Arg::with_name("record")
+                .long("record")
+                .help("Record spikes, effectors output and brain stats (CSV and binary files)"),
+        )
         .arg(
             Arg::with_name("verbose")
                 .short("v")
@@ -133,7 +198,7 @@ fn main() -> Result<()> {
                     Arg::with_name("type")
                         .short("t")
                         .long("type")
-                        .help("Template file type (builder, timeline)")
+                        .help("Template file type (builder, timeline, colors)")
                         .default_value("builder"),
                 )
                 .arg(
@@ -146,10 +211,23 @@ fn main() -> Result<()> {
                         .required(true),
                 ),
         )
+        .subcommand(
+            SubCommand::with_name("validate")
+                .about("Check brain snapshot integrity")
+                .arg(
+                    Arg::with_name("snapshot")
+                        .value_name("FILE")
+                        .help("Brain snapshot file path")
+                        .takes_value(true)
+                        .required(true),
+                ),
+        )
         .get_matches();
 
     if let Some(matches) = matches.subcommand_matches("template") {
         main_template(matches)
+    } else if let Some(matches) = matches.subcommand_matches("validate") {
+        main_validate(matches)
     } else {
         main_simulation(matches)
     }
@@ -179,6 +257,19 @@ fn main_template(matches: &ArgMatches) -> Result<()> {
                 "yaml" => write(output, Timeline::default().to_yaml().unwrap()).unwrap(),
                 name => panic!("Unsupported template format: {}", name),
             },
+            "colors" => match format {
+                "json" => write(
+                    output,
+                    serde_json::to_string_pretty(&SceneConfig::default()).unwrap(),
+                )
+                .unwrap(),
+                "yaml" => write(
+                    output,
+                    serde_yaml::to_string(&SceneConfig::default()).unwrap(),
+                )
+                .unwrap(),
+                name => panic!("Unsupported template format: {}", name),
+            },
             name => panic!("Unsupported template type: {}", name),
         }
     } else {
@@ -188,8 +279,18 @@ fn main_template(matches: &ArgMatches) -> Result<()> {
     Ok(())
 }
 
+fn main_validate(matches: &ArgMatches) -> Result<()> {
+    let snapshot = matches.value_of("snapshot").unwrap();
+    load_snapshot(snapshot)?.validate()?;
+    println!("Brain snapshot is valid: {}", snapshot);
+    Ok(())
+}
+
 fn main_simulation(matches: ArgMatches) -> Result<()> {
-    let mut brain = make_brain(&matches);
+    let seed = matches
+        .value_of("seed")
+        .map(|seed| seed.parse::<u64>().unwrap());
+    let mut brain = make_brain(&matches, seed)?;
     let timeline = make_timeline(&matches);
     let fps = matches.value_of("fps").unwrap().parse::<usize>().unwrap();
     let output_dir = Path::new(matches.value_of("output_dir").unwrap())
@@ -204,13 +305,51 @@ fn main_simulation(matches: ArgMatches) -> Result<()> {
     let render_effectors = !matches.is_present("ignore-effectors");
     let dry = matches.is_present("dry");
     let verbose = matches.is_present("verbose");
+    let colors = make_colors(&matches);
+    let render_ply = matches.is_present("ply");
+    let mut animation = if matches.is_present("gltf") {
+        Some(Animation::new(colors.clone()))
+    } else {
+        None
+    };
+    let render_svg = matches.is_present("svg");
+    let render_png = matches.is_present("png");
+    let render_overlay = matches.is_present("overlay");
+    let mut render_config = RenderConfig {
+        projection: match matches.value_of("projection").unwrap() {
+            "xy" => Projection::XY,
+            "xz" => Projection::XZ,
+            "yz" => Projection::YZ,
+            name => panic!("Unsupported projection: {}", name),
+        },
+        scene: colors.clone(),
+        ..Default::default()
+    };
+    let mut recorder = if matches.is_present("record") {
+        Some(Recorder::new().effectors(brain.get_effectors()).stats(true))
+    } else {
+        None
+    };
 
-    let mut rng = thread_rng();
+    let mut rng = if let Some(seed) = seed {
+        BrainRng::from_u64(seed)
+    } else {
+        BrainRng::from_entropy()
+    };
     let delta_time = 1.0 / fps as Scalar;
     let mut last_time = 0.0;
     let mut next_time = delta_time;
     let mut frame = 0;
-    let generator_config = Default::default();
+    let generator_config = ObjConfig {
+        scene: colors.clone(),
+        ..Default::default()
+    };
+    // dry runs do not render anything, so they do not track brain changes either.
+    let mut topology = if dry {
+        Default::default()
+    } else {
+        brain.build_topology()
+    };
     let timer = Instant::now();
     while let Some(actions) = timeline.perform(last_time, next_time) {
         println!(
@@ -299,18 +438,63 @@ fn main_simulation(matches: ArgMatches) -> Result<()> {
         if verbose {
             print_stats(brain.build_activity_stats());
         }
+        if let Some(recorder) = &mut recorder {
+            recorder.record(&brain);
+        }
         if !dry {
             println!("- writing snapshot");
+            let delta = brain.build_activity_delta(activity::ALL);
+            topology.apply(&delta);
+            let activity_map = topology.build_activity_map(&delta, activity::ALL);
             write(
                 format!("{}/{}-all-{}.obj", output_dir, name, frame),
-                generate(&brain.build_activity_map(activity::ALL), &generator_config)?,
+                generate(&activity_map, &generator_config)?,
             )
             .unwrap();
+            if render_ply || animation.is_some() || render_svg || render_png {
+                if render_svg || render_png {
+                    // keep the same view for whole frame sequence.
+                    if render_config.view.is_none() {
+                        render_config.view = Some(fit_view(&activity_map, &render_config));
+                    }
+                    let overlay = if render_overlay {
+                        let mut overlay = vec![format!("time: {:.3}", brain.time())];
+                        overlay.extend(stats_overlay(&brain.build_activity_stats()));
+                        overlay
+                    } else {
+                        vec![]
+                    };
+                    if render_svg {
+                        write(
+                            format!("{}/{}-{}.svg", output_dir, name, frame),
+                            svg::generate(&activity_map, &render_config, &overlay)?,
+                        )
+                        .unwrap();
+                    }
+                    if render_png {
+                        write(
+                            format!("{}/{}-{}.png", output_dir, name, frame),
+                            bitmap::generate(&activity_map, &render_config, &overlay)?,
+                        )
+                        .unwrap();
+                    }
+                }
+                if render_ply {
+                    write(
+                        format!("{}/{}-all-{}.ply", output_dir, name, frame),
+                        ply::generate(&activity_map, &colors)?,
+                    )
+                    .unwrap();
+                }
+                if let Some(animation) = &mut animation {
+                    animation.push(brain.time(), &activity_map);
+                }
+            }
             if render_neurons {
                 write(
                     format!("{}/{}-neurons-{}.obj", output_dir, name, frame),
                     generate(
-                        &brain.build_activity_map(activity::NEURONS),
+                        &select_activity(&activity_map, activity::NEURONS),
                         &generator_config,
                     )?,
                 )
@@ -320,7 +504,7 @@ fn main_simulation(matches: ArgMatches) -> Result<()> {
                 write(
                     format!("{}/{}-connections-{}.obj", output_dir, name, frame),
                     generate(
-                        &brain.build_activity_map(activity::CONNECTIONS),
+                        &select_activity(&activity_map, activity::CONNECTIONS),
                         &generator_config,
                     )?,
                 )
@@ -330,7 +514,7 @@ fn main_simulation(matches: ArgMatches) -> Result<()> {
                 write(
                     format!("{}/{}-impulses-{}.obj", output_dir, name, frame),
                     generate(
-                        &brain.build_activity_map(activity::IMPULSES),
+                        &select_activity(&activity_map, activity::IMPULSES),
                         &generator_config,
                     )?,
                 )
@@ -340,7 +524,7 @@ fn main_simulation(matches: ArgMatches) -> Result<()> {
                 write(
                     format!("{}/{}-sensors-{}.obj", output_dir, name, frame),
                     generate(
-                        &brain.build_activity_map(activity::SENSORS),
+                        &select_activity(&activity_map, activity::SENSORS),
                         &generator_config,
                     )?,
                 )
@@ -350,7 +534,7 @@ fn main_simulation(matches: ArgMatches) -> Result<()> {
                 write(
                     format!("{}/{}-effectors-{}.obj", output_dir, name, frame),
                     generate(
-                        &brain.build_activity_map(activity::EFFECTORS),
+                        &select_activity(&activity_map, activity::EFFECTORS),
                         &generator_config,
                     )?,
                 )
@@ -363,35 +547,82 @@ fn main_simulation(matches: ArgMatches) -> Result<()> {
         frame += 1;
     }
 
+    if let Some(animation) = animation {
+        println!("Writing animation");
+        write(
+            format!("{}/{}-animation.glb", output_dir, name),
+            animation.generate()?,
+        )
+        .unwrap();
+    }
+
+    if let Some(recorder) = recorder {
+        println!("Writing recording");
+        recorder.write_csv(File::create(format!(
+            "{}/{}-recording.csv",
+            output_dir, name
+        ))?)?;
+        recorder.write_spikes_csv(File::create(format!("{}/{}-spikes.csv", output_dir, name))?)?;
+        write(
+            format!("{}/{}-recording.bin", output_dir, name),
+            recorder_to_bytes(&recorder).unwrap(),
+        )
+        .unwrap();
+    }
+
     Ok(())
 }
 
-fn make_brain(matches: &ArgMatches) -> Brain {
+// takes parts of activity map selected by flags.
+fn select_activity(activity_map: &BrainActivityMap, flags: usize) -> BrainActivityMap {
+    let mut result = BrainActivityMap::default();
+    if flags & activity::CONNECTIONS != 0 {
+        result.connections = activity_map.connections.clone();
+        result.inhibitory_connections = activity_map.inhibitory_connections.clone();
+    }
+    if flags & activity::IMPULSES != 0 {
+        result.impulses = activity_map.impulses.clone();
+        result.inhibitory_impulses = activity_map.inhibitory_impulses.clone();
+        result.impulses_potential = activity_map.impulses_potential.clone();
+        result.inhibitory_impulses_potential = activity_map.inhibitory_impulses_potential.clone();
+    }
+    if flags & activity::SENSORS != 0 {
+        result.sensors = activity_map.sensors.clone();
+    }
+    if flags & activity::EFFECTORS != 0 {
+        result.effectors = activity_map.effectors.clone();
+    }
+    if flags & activity::NEURONS != 0 {
+        result.neurons = activity_map.neurons.clone();
+        result.inhibitory_neurons = activity_map.inhibitory_neurons.clone();
+        result.neurons_potential = activity_map.neurons_potential.clone();
+        result.inhibitory_neurons_potential = activity_map.inhibitory_neurons_potential.clone();
+    }
+    result
+}
+
+fn make_brain(matches: &ArgMatches, seed: Option<u64>) -> Result<Brain> {
     if let Some(snapshot) = matches.value_of("snapshot") {
-        if snapshot.ends_with(".json") {
-            brain_from_json(from_utf8(&read(snapshot).unwrap()).unwrap()).unwrap()
-        } else if snapshot.ends_with(".yaml") {
-            brain_from_yaml(from_utf8(&read(snapshot).unwrap()).unwrap()).unwrap()
-        } else {
-            panic!(
-                "Snapshot file with no specified format extension: {}",
-                snapshot
-            )
+        let mut brain = load_snapshot(snapshot)?;
+        if let Some(seed) = seed {
+            brain.reseed(seed);
         }
+        Ok(brain)
     } else if let Some(builder) = matches.value_of("builder") {
-        if builder.ends_with(".json") {
-            brain_builder_from_json(from_utf8(&read(builder).unwrap()).unwrap())
-                .unwrap()
-                .build()
+        let builder = if builder.ends_with(".json") {
+            brain_builder_from_json(from_utf8(&read(builder).unwrap()).unwrap()).unwrap()
         } else if builder.ends_with(".yaml") {
-            brain_builder_from_yaml(from_utf8(&read(builder).unwrap()).unwrap())
-                .unwrap()
-                .build()
+            brain_builder_from_yaml(from_utf8(&read(builder).unwrap()).unwrap()).unwrap()
         } else {
             panic!(
                 "Brain builder file with no specified format extension: {}",
                 builder
             )
+        };
+        if let Some(seed) = seed {
+            Ok(builder.seed(seed).build())
+        } else {
+            Ok(builder.build())
         }
     } else {
         let mut config = Config::default();
@@ -400,7 +631,33 @@ fn make_brain(matches: &ArgMatches) -> Brain {
         config.neuron_potential_decay = 0.1;
         config.synapse_propagation_decay = 0.01;
         config.synapse_new_connection_receptors = Some(2.0);
-        make_default_brain_builder(config).build()
+        let builder = make_default_brain_builder(config);
+        if let Some(seed) = seed {
+            Ok(builder.seed(seed).build())
+        } else {
+            Ok(builder.build())
+        }
+    }
+}
+
+fn load_snapshot(snapshot: &str) -> Result<Brain> {
+    let invalid = |error: &dyn Display| {
+        Error::simple(format!("Invalid brain snapshot {}: {}", snapshot, error))
+    };
+    let bytes = read(snapshot).map_err(|e| invalid(&e))?;
+    if snapshot.ends_with(".json") {
+        let json = from_utf8(&bytes).map_err(|e| invalid(&e))?;
+        brain_from_json(json).map_err(|e| invalid(&e))
+    } else if snapshot.ends_with(".yaml") {
+        let yaml = from_utf8(&bytes).map_err(|e| invalid(&e))?;
+        brain_from_yaml(yaml).map_err(|e| invalid(&e))
+    } else if snapshot.ends_with(".bin") {
+        brain_from_bytes(&bytes).map_err(|e| invalid(&e))
+    } else {
+        Err(Error::simple(format!(
+            "Snapshot file with no specified format extension: {}",
+            snapshot
+        )))
     }
 }
 
@@ -421,6 +678,20 @@ fn make_timeline(matches: &ArgMatches) -> Timeline {
     }
 }
 
+fn make_colors(matches: &ArgMatches) -> SceneConfig {
+    if let Some(colors) = matches.value_of("colors") {
+        if colors.ends_with(".json") {
+            serde_json::from_str(from_utf8(&read(colors).unwrap()).unwrap()).unwrap()
+        } else if colors.ends_with(".yaml") {
+            serde_yaml::from_str(from_utf8(&read(colors).unwrap()).unwrap()).unwrap()
+        } else {
+            panic!("Colors file with no specified format extension: {}", colors)
+        }
+    } else {
+        Default::default()
+    }
+}
+
 fn print_stats(stats: BrainActivityStats) {
     println!("- brain activity stats:");
     println!("  Count:");
@@ -441,12 +712,34 @@ fn print_stats(stats: BrainActivityStats) {
     println!("  - Incoming:");
     println!("    - min: {}", stats.incoming_neuron_connections.start);
     println!("    - max: {}", stats.incoming_neuron_connections.end);
+    println!(
+        "    - histogram: {:?}",
+        stats.incoming_neuron_connections_histogram
+    );
     println!("  - Outgoing:");
     println!("    - min: {}", stats.outgoing_neuron_connections.start);
     println!("    - max: {}", stats.outgoing_neuron_connections.end);
+    println!(
+        "    - histogram: {:?}",
+        stats.outgoing_neuron_connections_histogram
+    );
     println!("  Synapses receptors:");
     println!("  - min: {}", stats.synapses_receptors.start);
     println!("  - max: {}", stats.synapses_receptors.end);
+    println!("  - histogram: {:?}", stats.synapses_receptors_histogram);
+    println!("  Firing:");
+    println!("  - mean rate: {}", stats.mean_firing_rate);
+    println!("  - active neurons: {}", stats.active_neurons);
+    println!("  Graph:");
+    println!(
+        "  - strongly connected components: {}",
+        stats.strongly_connected_components
+    );
+    println!(
+        "  - sensor to effector path length: {}",
+        stats.sensor_effector_path_length
+    );
+    println!("  - reachable effectors: {}", stats.reachable_effectors);
 }
 
 fn make_default_brain_builder(config: Config) -> BrainBuilder {
@@ -1,4 +1,5 @@
 extern crate psyche_core;
+extern crate psyche_evolution;
 extern crate psyche_graphics;
 extern crate psyche_host;
 extern crate psyche_serde;
@@ -19,3 +20,6 @@ pub mod host {
 pub mod graphics {
     pub use psyche_graphics::*;
 }
+pub mod evolution {
+    pub use psyche_evolution::*;
+}